@@ -0,0 +1,260 @@
+use std::{collections::BTreeMap, thread};
+
+use crate::{base_channel::DataChannel, thread_rt, Error, Result};
+
+type Worker = Box<dyn FnOnce() -> Result<()> + Send + 'static>;
+
+/// A channel adapter. Can be driven either inline with [`Adapter::run`] or by a dedicated thread
+/// with [`Adapter::spawn`].
+///
+/// Shutdown is propagated through [`Error::ChannelClosed`]: an adapter stops when all its inputs
+/// are closed or all its outputs are closed (the latter is noticed on the next incoming value),
+/// dropping its channel ends and closing them for the neighbours.
+pub struct Adapter {
+    workers: Vec<Worker>,
+}
+
+impl Adapter {
+    fn new(workers: Vec<Worker>) -> Self {
+        Self { workers }
+    }
+    /// Runs the adapter in the current thread until shutdown. Adapters with multiple inputs (see
+    /// [`merge`]) use additional scoped threads
+    pub fn run(self) -> Result<()> {
+        Self::run_workers(self.workers, None)
+    }
+    /// Runs the adapter in a dedicated thread with the given real-time parameters. If the
+    /// parameters can not be applied, the thread is terminated with the error. Adapters with
+    /// multiple inputs (see [`merge`]) use additional threads with the same parameters
+    pub fn spawn(self, params: thread_rt::Params) -> thread::JoinHandle<Result<()>> {
+        thread::spawn(move || {
+            thread_rt::apply_for_current(&params)?;
+            Self::run_workers(self.workers, Some(&params))
+        })
+    }
+    fn run_workers(mut workers: Vec<Worker>, params: Option<&thread_rt::Params>) -> Result<()> {
+        let Some(first) = workers.pop() else {
+            return Ok(());
+        };
+        thread::scope(|scope| {
+            let handles: Vec<_> = workers
+                .into_iter()
+                .map(|worker| {
+                    scope.spawn(move || {
+                        if let Some(params) = params {
+                            thread_rt::apply_for_current(params)?;
+                        }
+                        worker()
+                    })
+                })
+                .collect();
+            let mut result = first();
+            for handle in handles {
+                let res = handle
+                    .join()
+                    .map_err(|_| Error::Failed("worker panicked".to_owned()));
+                if result.is_ok() {
+                    result = res.and_then(|r| r);
+                }
+            }
+            result
+        })
+    }
+}
+
+/// Receives a value, returns None if the input is closed
+fn recv<T, R: DataChannel<T>>(rx: &R) -> Result<Option<T>> {
+    match rx.recv() {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::ChannelClosed) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Sends a value, returns false if the output is closed. Skipped optional values are ignored
+fn send<T, S: DataChannel<T>>(tx: &S, value: T) -> Result<bool> {
+    match tx.send(value) {
+        Ok(()) | Err(Error::ChannelSkipped) => Ok(true),
+        Err(Error::ChannelClosed) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Creates an adapter which transforms values from the input and sends them to the output
+pub fn map<T, U, R, S, F>(rx: R, tx: S, mut f: F) -> Adapter
+where
+    R: DataChannel<T> + Send + 'static,
+    S: DataChannel<U> + Send + 'static,
+    F: FnMut(T) -> U + Send + 'static,
+{
+    Adapter::new(vec![Box::new(move || {
+        while let Some(value) = recv(&rx)? {
+            if !send(&tx, f(value))? {
+                break;
+            }
+        }
+        Ok(())
+    })])
+}
+
+/// Creates an adapter which sends to the output only values matching the predicate
+pub fn filter<T, R, S, F>(rx: R, tx: S, mut f: F) -> Adapter
+where
+    R: DataChannel<T> + Send + 'static,
+    S: DataChannel<T> + Send + 'static,
+    F: FnMut(&T) -> bool + Send + 'static,
+{
+    Adapter::new(vec![Box::new(move || {
+        while let Some(value) = recv(&rx)? {
+            if f(&value) && !send(&tx, value)? {
+                break;
+            }
+        }
+        Ok(())
+    })])
+}
+
+/// Creates an adapter which merges multiple inputs into a single output. The output is closed
+/// when all the inputs are closed
+pub fn merge<T, R, S>(rxs: Vec<R>, tx: S) -> Adapter
+where
+    R: DataChannel<T> + Send + 'static,
+    S: DataChannel<T> + Clone + Send + 'static,
+{
+    Adapter::new(
+        rxs.into_iter()
+            .map(|rx| {
+                let tx = tx.clone();
+                Box::new(move || {
+                    while let Some(value) = recv(&rx)? {
+                        if !send(&tx, value)? {
+                            break;
+                        }
+                    }
+                    Ok(())
+                }) as Worker
+            })
+            .collect(),
+    )
+}
+
+/// Creates an adapter which routes values from the input to the outputs by key. Values with keys
+/// having no output are dropped. Closed outputs are removed, the adapter stops when all the
+/// outputs are closed
+pub fn route<T, K, R, S, F>(rx: R, mut txs: BTreeMap<K, S>, mut f: F) -> Adapter
+where
+    K: Ord + Send + 'static,
+    R: DataChannel<T> + Send + 'static,
+    S: DataChannel<T> + Send + 'static,
+    F: FnMut(&T) -> K + Send + 'static,
+{
+    Adapter::new(vec![Box::new(move || {
+        while let Some(value) = recv(&rx)? {
+            let key = f(&value);
+            if let Some(tx) = txs.get(&key) {
+                if !send(tx, value)? {
+                    txs.remove(&key);
+                    if txs.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    })])
+}
+
+/// Creates an adapter which splits values from the input to the outputs by index (see
+/// [`route`])
+pub fn split<T, R, S, F>(rx: R, txs: Vec<S>, f: F) -> Adapter
+where
+    R: DataChannel<T> + Send + 'static,
+    S: DataChannel<T> + Send + 'static,
+    F: FnMut(&T) -> usize + Send + 'static,
+{
+    route(rx, txs.into_iter().enumerate().collect(), f)
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use crate::{channel::Bounded, thread_rt::Params, Error};
+
+    use super::{filter, map, merge, split};
+
+    #[test]
+    fn test_map_filter() {
+        let Bounded { tx, rx } = Bounded::<u32>::new(4);
+        let Bounded { tx: tx_m, rx: rx_m } = Bounded::<String>::new(4);
+        let Bounded {
+            tx: tx_out,
+            rx: rx_out,
+        } = Bounded::<String>::new(4);
+        let h_map = map(rx, tx_m, |v| v.to_string()).spawn(Params::new());
+        let h_filter = filter(rx_m, tx_out, |v: &String| v.len() > 1).spawn(Params::new());
+        for i in 5..15 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+        let result: Vec<String> = rx_out.collect();
+        insta::assert_debug_snapshot!(result, @r###"
+        [
+            "10",
+            "11",
+            "12",
+            "13",
+            "14",
+        ]
+        "###);
+        h_map.join().unwrap().unwrap();
+        h_filter.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_merge() {
+        let Bounded { tx: tx1, rx: rx1 } = Bounded::<u32>::new(4);
+        let Bounded { tx: tx2, rx: rx2 } = Bounded::<u32>::new(4);
+        let Bounded {
+            tx: tx_out,
+            rx: rx_out,
+        } = Bounded::<u32>::new(32);
+        let handle = thread::spawn(move || merge(vec![rx1, rx2], tx_out).run());
+        for i in 0..10 {
+            tx1.send(i).unwrap();
+            tx2.send(i + 100).unwrap();
+        }
+        drop(tx1);
+        drop(tx2);
+        let mut result: Vec<u32> = rx_out.collect();
+        result.sort_unstable();
+        assert_eq!(
+            result,
+            (0..10).chain(100..110).collect::<Vec<_>>(),
+            "values are lost"
+        );
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_split_shutdown() {
+        let Bounded { tx, rx } = Bounded::<u32>::new(4);
+        let Bounded { tx: tx_e, rx: rx_e } = Bounded::<u32>::new(16);
+        let Bounded { tx: tx_o, rx: rx_o } = Bounded::<u32>::new(16);
+        let handle = split(rx, vec![tx_e, tx_o], |v| (*v % 2) as usize).spawn(Params::new());
+        for i in 0..10 {
+            tx.send(i).unwrap();
+        }
+        for i in 0..5 {
+            assert_eq!(rx_e.recv().unwrap(), i * 2);
+            assert_eq!(rx_o.recv().unwrap(), i * 2 + 1);
+        }
+        drop(rx_e);
+        drop(rx_o);
+        // the adapter notices closed outputs on the next values and closes the input
+        tx.send(10).unwrap();
+        tx.send(11).unwrap();
+        handle.join().unwrap().unwrap();
+        assert!(matches!(tx.send(12), Err(Error::ChannelClosed)));
+    }
+}
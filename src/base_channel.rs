@@ -1,4 +1,13 @@
-use std::{marker::PhantomData, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    marker::PhantomData,
+    mem,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
 
 use crate::condvar_api::RawCondvar;
 use crate::locking::{Condvar, RawMutex};
@@ -22,6 +31,22 @@ pub trait ChannelStorage<T: Sized> {
     fn is_full(&self) -> bool;
     /// Returns true if the storage is empty
     fn is_empty(&self) -> bool;
+    /// Returns a reference to the next value without removing it from the storage (optional)
+    fn peek(&self) -> Result<Option<&T>> {
+        Err(Error::Unimplemented)
+    }
+    /// Retains only the values specified by the predicate, returns the number of removed values
+    /// (optional)
+    fn retain<F>(&mut self, _f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        Err(Error::Unimplemented)
+    }
+    /// Removes all values from the storage, returns the number of removed values (optional)
+    fn clear(&mut self) -> Result<usize> {
+        Err(Error::Unimplemented)
+    }
 }
 
 /// An abstract trait for data channels and hubs
@@ -40,6 +65,160 @@ pub trait DataChannel<T: Sized> {
     }
 }
 
+/// A boxed future, returned by async methods of [`DataSender`] and [`DataReceiver`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// An object-safe trait for data senders (channel senders, cells etc.)
+///
+/// Async methods return [`Error::Unimplemented`] if not supported by the sender.
+pub trait DataSender<T> {
+    /// Sends a value, blocks if there is no room
+    fn send(&self, value: T) -> Result<()>;
+    /// Sends a value with a timeout. Senders which never block (e.g. cells, which overwrite the
+    /// previous value) ignore the timeout and behave the same as [`DataSender::send`]
+    fn send_timeout(&self, value: T, timeout: Duration) -> Result<()>;
+    /// Tries to send a value (non-blocking). Senders which never block behave the same as
+    /// [`DataSender::send`]
+    fn try_send(&self, value: T) -> Result<()>;
+    /// Sends a value asynchronously
+    fn send_async<'a>(&'a self, value: T) -> BoxFuture<'a, Result<()>>
+    where
+        T: Send + 'a,
+    {
+        drop(value);
+        Box::pin(std::future::ready(Err(Error::Unimplemented)))
+    }
+    /// Returns true if the receiving side is alive
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+/// An object-safe trait for data receivers (channel receivers, cells etc.)
+///
+/// Async methods return [`Error::Unimplemented`] if not supported by the receiver.
+pub trait DataReceiver<T> {
+    /// Receives a value, blocks until one is available
+    fn recv(&self) -> Result<T>;
+    /// Receives a value with a timeout
+    fn recv_timeout(&self, timeout: Duration) -> Result<T>;
+    /// Tries to receive a value (non-blocking)
+    fn try_recv(&self) -> Result<T>;
+    /// Receives a value asynchronously
+    fn recv_async<'a>(&'a self) -> BoxFuture<'a, Result<T>>
+    where
+        T: Send + 'a,
+    {
+        Box::pin(std::future::ready(Err(Error::Unimplemented)))
+    }
+    /// Returns true if the sending side is alive
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+impl<T, D> DataSender<T> for Box<D>
+where
+    D: DataSender<T> + ?Sized,
+{
+    fn send(&self, value: T) -> Result<()> {
+        (**self).send(value)
+    }
+    fn send_timeout(&self, value: T, timeout: Duration) -> Result<()> {
+        (**self).send_timeout(value, timeout)
+    }
+    fn try_send(&self, value: T) -> Result<()> {
+        (**self).try_send(value)
+    }
+    fn send_async<'a>(&'a self, value: T) -> BoxFuture<'a, Result<()>>
+    where
+        T: Send + 'a,
+    {
+        (**self).send_async(value)
+    }
+    fn is_alive(&self) -> bool {
+        (**self).is_alive()
+    }
+}
+
+impl<T, D> DataReceiver<T> for Box<D>
+where
+    D: DataReceiver<T> + ?Sized,
+{
+    fn recv(&self) -> Result<T> {
+        (**self).recv()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        (**self).recv_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<T> {
+        (**self).try_recv()
+    }
+    fn recv_async<'a>(&'a self) -> BoxFuture<'a, Result<T>>
+    where
+        T: Send + 'a,
+    {
+        (**self).recv_async()
+    }
+    fn is_alive(&self) -> bool {
+        (**self).is_alive()
+    }
+}
+
+impl<T, S, M, CV> DataSender<T> for BaseSender<T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T> + Send,
+    M: RawMutexTrait + Sync,
+    CV: RawCondvar + RawCondvar<RawMutex = M> + Sync,
+{
+    fn send(&self, value: T) -> Result<()> {
+        self.send(value)
+    }
+    fn send_timeout(&self, value: T, timeout: Duration) -> Result<()> {
+        self.send_timeout(value, timeout)
+    }
+    fn try_send(&self, value: T) -> Result<()> {
+        self.try_send(value)
+    }
+    fn send_async<'a>(&'a self, value: T) -> BoxFuture<'a, Result<()>>
+    where
+        T: Send + 'a,
+    {
+        Box::pin(self.send_async(value))
+    }
+    fn is_alive(&self) -> bool {
+        self.is_alive()
+    }
+}
+
+impl<T, S, M, CV> DataReceiver<T> for BaseReceiver<T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T> + Send,
+    M: RawMutexTrait + Sync,
+    CV: RawCondvar + RawCondvar<RawMutex = M> + Sync,
+{
+    fn recv(&self) -> Result<T> {
+        self.recv()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        self.recv_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<T> {
+        self.try_recv()
+    }
+    fn recv_async<'a>(&'a self) -> BoxFuture<'a, Result<T>>
+    where
+        T: Send + 'a,
+    {
+        Box::pin(self.recv_async())
+    }
+    fn is_alive(&self) -> bool {
+        self.is_alive()
+    }
+}
+
 impl<T, S, M, CV> DataChannel<T> for BaseSender<T, S, M, CV>
 where
     T: Sized,
@@ -161,6 +340,16 @@ where
     M: RawMutexTrait,
     CV: RawCondvar + RawCondvar<RawMutex = M>,
 {
+    #[inline]
+    fn notify_data_sent(&self, data: &mut InnerData<T, S>) {
+        self.data_available.notify_one();
+        data.wake_receivers();
+    }
+    #[inline]
+    fn notify_data_received(&self, data: &mut InnerData<T, S>) {
+        self.space_available.notify_one();
+        data.wake_senders();
+    }
     fn send(&self, mut value: T) -> Result<()> {
         let mut data = self.data.lock();
         let pushed = loop {
@@ -176,7 +365,7 @@ where
         };
         match pushed {
             StorageTryPushOutput::Pushed => {
-                self.data_available.notify_one();
+                self.notify_data_sent(&mut data);
                 Ok(())
             }
             StorageTryPushOutput::Skipped => Err(Error::ChannelSkipped),
@@ -184,19 +373,19 @@ where
         }
     }
     fn send_timeout(&self, mut value: T, timeout: Duration) -> Result<()> {
-        let mut pc = self.data.lock();
+        let mut data = self.data.lock();
         let pushed = loop {
-            if pc.receivers == 0 {
+            if data.receivers == 0 {
                 return Err(Error::ChannelClosed);
             }
-            let push_result = pc.queue.try_push(value);
+            let push_result = data.queue.try_push(value);
             let StorageTryPushOutput::Full(val) = push_result else {
                 break push_result;
             };
             value = val;
             if self
                 .space_available
-                .wait_for::<InnerData<T, S>, M>(&mut pc, timeout)
+                .wait_for::<InnerData<T, S>, M>(&mut data, timeout)
                 .timed_out()
             {
                 return Err(Error::Timeout);
@@ -204,7 +393,7 @@ where
         };
         match pushed {
             StorageTryPushOutput::Pushed => {
-                self.data_available.notify_one();
+                self.notify_data_sent(&mut data);
                 Ok(())
             }
             StorageTryPushOutput::Skipped => Err(Error::ChannelSkipped),
@@ -218,7 +407,7 @@ where
         }
         match data.queue.try_push(value) {
             StorageTryPushOutput::Pushed => {
-                self.data_available.notify_one();
+                self.notify_data_sent(&mut data);
                 Ok(())
             }
             StorageTryPushOutput::Skipped => Err(Error::ChannelSkipped),
@@ -229,7 +418,7 @@ where
         let mut data = self.data.lock();
         loop {
             if let Some(val) = data.queue.get() {
-                self.space_available.notify_one();
+                self.notify_data_received(&mut data);
                 return Ok(val);
             } else if data.senders == 0 {
                 return Err(Error::ChannelClosed);
@@ -241,7 +430,7 @@ where
         let mut data = self.data.lock();
         loop {
             if let Some(val) = data.queue.get() {
-                self.space_available.notify_one();
+                self.notify_data_received(&mut data);
                 return Ok(val);
             } else if data.senders == 0 {
                 return Err(Error::ChannelClosed);
@@ -256,16 +445,50 @@ where
         }
     }
     fn try_recv(&self) -> Result<T> {
-        let mut pc = self.data.lock();
-        if let Some(val) = pc.queue.get() {
-            self.space_available.notify_one();
+        let mut data = self.data.lock();
+        if let Some(val) = data.queue.get() {
+            self.notify_data_received(&mut data);
             Ok(val)
+        } else if data.senders == 0 {
+            Err(Error::ChannelClosed)
+        } else {
+            Err(Error::ChannelEmpty)
+        }
+    }
+    fn peek_with<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let pc = self.data.lock();
+        if let Some(val) = pc.queue.peek()? {
+            Ok(f(val))
         } else if pc.senders == 0 {
             Err(Error::ChannelClosed)
         } else {
             Err(Error::ChannelEmpty)
         }
     }
+    fn retain<F>(&self, f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut pc = self.data.lock();
+        let removed = pc.queue.retain(f)?;
+        if removed > 0 {
+            self.space_available.notify_all();
+            pc.wake_senders();
+        }
+        Ok(removed)
+    }
+    fn clear(&self) -> Result<usize> {
+        let mut pc = self.data.lock();
+        let removed = pc.queue.clear()?;
+        if removed > 0 {
+            self.space_available.notify_all();
+            pc.wake_senders();
+        }
+        Ok(removed)
+    }
 }
 
 impl<T, S, M, CV> BaseChannel<T, S, M, CV>
@@ -276,11 +499,21 @@ where
     CV: RawCondvar,
 {
     /// Creates a new channel with the specified capacity and ordering
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the capacity is zero
     pub fn new(capacity: usize, ordering: bool) -> Self {
+        assert!(capacity > 0, "channel capacity MUST be > 0");
+        Self::with_storage(S::with_capacity_and_ordering(capacity, ordering))
+    }
+    /// Creates a new channel with a pre-built storage (e.g. to use storages with custom
+    /// capacity rules)
+    pub fn with_storage(storage: S) -> Self {
         Self(
             ChannelInner {
                 id: <_>::default(),
-                data: lock_api::Mutex::const_new(M::INIT, InnerData::new(capacity, ordering)),
+                data: lock_api::Mutex::const_new(M::INIT, InnerData::new(storage)),
                 data_available: CV::new(),
                 space_available: CV::new(),
             }
@@ -297,6 +530,10 @@ where
     queue: S,
     senders: usize,
     receivers: usize,
+    // wakers of pending async senders and receivers, by future id
+    send_wakers: BTreeMap<usize, Waker>,
+    recv_wakers: BTreeMap<usize, Waker>,
+    next_waker_id: usize,
     _phantom: PhantomData<T>,
 }
 
@@ -304,15 +541,32 @@ impl<T, S> InnerData<T, S>
 where
     S: ChannelStorage<T>,
 {
-    fn new(capacity: usize, ordering: bool) -> Self {
-        assert!(capacity > 0, "channel capacity MUST be > 0");
+    fn new(queue: S) -> Self {
         Self {
-            queue: S::with_capacity_and_ordering(capacity, ordering),
+            queue,
             senders: 1,
             receivers: 1,
+            send_wakers: BTreeMap::new(),
+            recv_wakers: BTreeMap::new(),
+            next_waker_id: 0,
             _phantom: PhantomData,
         }
     }
+    fn waker_id(&mut self) -> usize {
+        self.next_waker_id = self.next_waker_id.wrapping_add(1);
+        self.next_waker_id
+    }
+    // all pending futures are woken up and re-register themselves if still pending
+    fn wake_senders(&mut self) {
+        for waker in mem::take(&mut self.send_wakers).into_values() {
+            waker.wake();
+        }
+    }
+    fn wake_receivers(&mut self) {
+        for waker in mem::take(&mut self.recv_wakers).into_values() {
+            waker.wake();
+        }
+    }
 }
 
 /// Base channel sender
@@ -339,6 +593,16 @@ where
     pub fn send(&self, value: T) -> Result<()> {
         self.channel.0.send(value)
     }
+    /// Sends a value to the channel asynchronously. Allows async tasks to share the channel
+    /// with real-time threads without switching the locking policy. Works with any async runtime
+    #[inline]
+    pub fn send_async(&self, value: T) -> impl Future<Output = Result<()>> + '_ {
+        SendAsync {
+            inner: &self.channel.0,
+            value: Some(value),
+            id: None,
+        }
+    }
     /// Sends a value to the channel with a timeout
     #[inline]
     pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<()> {
@@ -398,6 +662,7 @@ where
         pc.senders -= 1;
         if pc.senders == 0 {
             self.channel.0.data_available.notify_all();
+            pc.wake_receivers();
         }
     }
 }
@@ -449,6 +714,39 @@ where
     pub fn try_recv(&self) -> Result<T> {
         self.channel.0.try_recv()
     }
+    /// Receives a value from the channel asynchronously. Works with any async runtime
+    #[inline]
+    pub fn recv_async(&self) -> impl Future<Output = Result<T>> + '_ {
+        RecvAsync {
+            inner: &self.channel.0,
+            id: None,
+        }
+    }
+    /// Calls the function with a reference to the next value without receiving it
+    /// (non-blocking). Requires the channel storage to support peeking
+    #[inline]
+    pub fn peek_with<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.channel.0.peek_with(f)
+    }
+    /// Retains only the values specified by the predicate, returns the number of removed values.
+    /// Blocked senders are woken up if space has been freed. Requires the channel storage to
+    /// support retaining
+    #[inline]
+    pub fn retain<F>(&self, f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.channel.0.retain(f)
+    }
+    /// Removes all values from the channel, returns the number of removed values. Blocked senders
+    /// are woken up if space has been freed. Requires the channel storage to support clearing
+    #[inline]
+    pub fn clear(&self) -> Result<usize> {
+        self.channel.0.clear()
+    }
     /// Returns the length of the channel storage
     #[inline]
     pub fn len(&self) -> usize {
@@ -497,7 +795,132 @@ where
         let mut pc = self.channel.0.data.lock();
         pc.receivers -= 1;
         if pc.receivers == 0 {
-            self.channel.0.data_available.notify_all();
+            self.channel.0.space_available.notify_all();
+            pc.wake_senders();
+        }
+    }
+}
+
+struct SendAsync<'a, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    inner: &'a ChannelInner<T, S, M, CV>,
+    value: Option<T>,
+    // set when the waker is registered
+    id: Option<usize>,
+}
+
+impl<T, S, M, CV> Unpin for SendAsync<'_, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+}
+
+impl<T, S, M, CV> Future for SendAsync<'_, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    type Output = Result<()>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner;
+        let mut data = inner.data.lock();
+        if let Some(id) = self.id.take() {
+            data.send_wakers.remove(&id);
+        }
+        if data.receivers == 0 {
+            return Poll::Ready(Err(Error::ChannelClosed));
+        }
+        let value = self.value.take().expect("value already sent");
+        match data.queue.try_push(value) {
+            StorageTryPushOutput::Pushed => {
+                inner.notify_data_sent(&mut data);
+                Poll::Ready(Ok(()))
+            }
+            StorageTryPushOutput::Skipped => Poll::Ready(Err(Error::ChannelSkipped)),
+            StorageTryPushOutput::Full(value) => {
+                self.value = Some(value);
+                let id = data.waker_id();
+                data.send_wakers.insert(id, cx.waker().clone());
+                self.id = Some(id);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T, S, M, CV> Drop for SendAsync<'_, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.inner.data.lock().send_wakers.remove(&id);
+        }
+    }
+}
+
+struct RecvAsync<'a, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    inner: &'a ChannelInner<T, S, M, CV>,
+    // set when the waker is registered
+    id: Option<usize>,
+}
+
+impl<T, S, M, CV> Future for RecvAsync<'_, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    type Output = Result<T>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner;
+        let mut data = inner.data.lock();
+        if let Some(id) = self.id.take() {
+            data.recv_wakers.remove(&id);
+        }
+        if let Some(val) = data.queue.get() {
+            inner.notify_data_received(&mut data);
+            return Poll::Ready(Ok(val));
+        } else if data.senders == 0 {
+            return Poll::Ready(Err(Error::ChannelClosed));
+        }
+        let id = data.waker_id();
+        data.recv_wakers.insert(id, cx.waker().clone());
+        self.id = Some(id);
+        Poll::Pending
+    }
+}
+
+impl<T, S, M, CV> Drop for RecvAsync<'_, T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.inner.data.lock().recv_wakers.remove(&id);
         }
     }
 }
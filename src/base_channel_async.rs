@@ -12,57 +12,85 @@ use std::{
     time::Duration,
 };
 
-use crate::{base_channel::ChannelStorage, data_policy::StorageTryPushOutput, Error, Result};
+use crate::{
+    base_channel::{BoxFuture, ChannelStorage, DataReceiver, DataSender},
+    data_policy::StorageTryPushOutput,
+    timer, Error, Result,
+};
+use crate::{
+    condvar_api::RawCondvar,
+    locking::{Condvar, RawMutex},
+};
+use lock_api::RawMutex as RawMutexTrait;
 use object_id::UniqueId;
-use parking_lot_rt::{Condvar, Mutex};
 use pin_project::{pin_project, pinned_drop};
 
 type ClientId = usize;
 
 /// Base async channel
-pub struct BaseChannelAsync<T: Sized, S: ChannelStorage<T>>(pub(crate) Arc<ChannelInner<T, S>>);
+pub struct BaseChannelAsync<T: Sized, S: ChannelStorage<T>, M = RawMutex, CV = Condvar>(
+    pub(crate) Arc<ChannelInner<T, S, M, CV>>,
+)
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>;
 
-impl<T: Sized, S: ChannelStorage<T>> BaseChannelAsync<T, S> {
+impl<T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>>
+    BaseChannelAsync<T, S, M, CV>
+{
     fn id(&self) -> usize {
         self.0.id.as_usize()
     }
 }
 
-impl<T: Sized, S: ChannelStorage<T>> Eq for BaseChannelAsync<T, S> {}
+impl<T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>> Eq
+    for BaseChannelAsync<T, S, M, CV>
+{
+}
 
-impl<T: Sized, S: ChannelStorage<T>> PartialEq for BaseChannelAsync<T, S> {
+impl<T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>> PartialEq
+    for BaseChannelAsync<T, S, M, CV>
+{
     fn eq(&self, other: &Self) -> bool {
         self.id() == other.id()
     }
 }
 
-impl<T, S> Clone for BaseChannelAsync<T, S>
+impl<T, S, M, CV> Clone for BaseChannelAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     fn clone(&self) -> Self {
         Self(self.0.clone())
     }
 }
 
-pub(crate) struct ChannelInner<T: Sized, S: ChannelStorage<T>> {
+pub(crate) struct ChannelInner<T: Sized, S: ChannelStorage<T>, M, CV> {
     id: UniqueId,
-    pub(crate) data: Mutex<InnerData<T, S>>,
+    pub(crate) data: lock_api::Mutex<M, InnerData<T, S, CV>>,
     next_op_id: AtomicUsize,
-    space_available: Arc<Condvar>,
-    data_available: Arc<Condvar>,
+    space_available: Arc<CV>,
+    data_available: Arc<CV>,
 }
 
-impl<T: Sized, S: ChannelStorage<T>> BaseChannelAsync<T, S> {
+impl<T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>>
+    BaseChannelAsync<T, S, M, CV>
+{
     pub(crate) fn new(capacity: usize, ordering: bool) -> Self {
-        let pc = InnerData::new(capacity, ordering);
+        assert!(capacity > 0, "channel capacity MUST be > 0");
+        Self::with_storage(S::with_capacity_and_ordering(capacity, ordering))
+    }
+    pub(crate) fn with_storage(storage: S) -> Self {
+        let pc = InnerData::<T, S, CV>::new(storage);
         let space_available = pc.space_available.clone();
         let data_available = pc.data_available.clone();
         Self(
             ChannelInner {
                 id: <_>::default(),
-                data: Mutex::new(pc),
+                data: lock_api::Mutex::const_new(M::INIT, pc),
                 next_op_id: <_>::default(),
                 space_available,
                 data_available,
@@ -75,7 +103,7 @@ impl<T: Sized, S: ChannelStorage<T>> BaseChannelAsync<T, S> {
     }
 }
 
-pub(crate) struct InnerData<T: Sized, S: ChannelStorage<T>> {
+pub(crate) struct InnerData<T: Sized, S: ChannelStorage<T>, CV> {
     queue: S,
     senders: usize,
     receivers: usize,
@@ -85,20 +113,20 @@ pub(crate) struct InnerData<T: Sized, S: ChannelStorage<T>> {
     pub(crate) recv_fut_wakers: VecDeque<Option<(Waker, ClientId)>>,
     pub(crate) recv_fut_waker_ids: BTreeSet<ClientId>,
     pub(crate) recv_fut_pending: BTreeSet<ClientId>,
-    data_available: Arc<Condvar>,
-    space_available: Arc<Condvar>,
+    data_available: Arc<CV>,
+    space_available: Arc<CV>,
     _phatom: PhantomData<T>,
 }
 
-impl<T, S> InnerData<T, S>
+impl<T, S, CV> InnerData<T, S, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    CV: RawCondvar,
 {
-    fn new(capacity: usize, ordering: bool) -> Self {
-        assert!(capacity > 0, "channel capacity MUST be > 0");
+    fn new(queue: S) -> Self {
         Self {
-            queue: S::with_capacity_and_ordering(capacity, ordering),
+            queue,
             senders: 1,
             receivers: 1,
             send_fut_wakers: <_>::default(),
@@ -107,8 +135,8 @@ where
             recv_fut_wakers: <_>::default(),
             recv_fut_waker_ids: <_>::default(),
             recv_fut_pending: <_>::default(),
-            data_available: <_>::default(),
-            space_available: <_>::default(),
+            data_available: CV::new().into(),
+            space_available: CV::new().into(),
             _phatom: PhantomData,
         }
     }
@@ -240,16 +268,18 @@ where
 }
 
 #[pin_project(PinnedDrop)]
-struct Send<'a, T: Sized, S: ChannelStorage<T>> {
+struct Send<'a, T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>> {
     id: usize,
-    channel: &'a BaseChannelAsync<T, S>,
+    channel: &'a BaseChannelAsync<T, S, M, CV>,
     queued: bool,
     value: Option<T>,
 }
 
 #[pinned_drop]
 #[allow(clippy::needless_lifetimes)]
-impl<'a, T: Sized, S: ChannelStorage<T>> PinnedDrop for Send<'a, T, S> {
+impl<'a, T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>> PinnedDrop
+    for Send<'a, T, S, M, CV>
+{
     fn drop(self: Pin<&mut Self>) {
         if self.queued {
             self.channel.0.data.lock().notify_send_fut_drop(self.id);
@@ -257,10 +287,12 @@ impl<'a, T: Sized, S: ChannelStorage<T>> PinnedDrop for Send<'a, T, S> {
     }
 }
 
-impl<T, S> Future for Send<'_, T, S>
+impl<T, S, M, CV> Future for Send<'_, T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     type Output = Result<()>;
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
@@ -296,18 +328,22 @@ where
 
 /// Base async sender
 #[derive(Eq, PartialEq)]
-pub struct BaseSenderAsync<T, S>
+pub struct BaseSenderAsync<T, S, M = RawMutex, CV = Condvar>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
-    channel: BaseChannelAsync<T, S>,
+    pub(crate) channel: BaseChannelAsync<T, S, M, CV>,
 }
 
-impl<T, S> BaseSenderAsync<T, S>
+impl<T, S, M, CV> BaseSenderAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     /// Sends a value to the channel
     #[inline]
@@ -319,6 +355,16 @@ where
             value: Some(value),
         }
     }
+    /// Sends a value to the channel with the given timeout. The timeout is handled by the shared
+    /// [`crate::timer`] thread, so the method does not depend on any async runtime
+    pub fn send_timeout(
+        &self,
+        value: T,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<()>> + '_ {
+        let fut = timer::timeout(timeout, self.send(value));
+        async move { fut.await? }
+    }
     /// Tries to send a value to the channel
     pub fn try_send(&self, value: T) -> Result<()> {
         let mut pc = self.channel.0.data.lock();
@@ -347,7 +393,10 @@ where
             };
             value = val;
             pc.append_send_sync_waker();
-            self.channel.0.space_available.wait(&mut pc);
+            self.channel
+                .0
+                .space_available
+                .wait::<InnerData<T, S, CV>, M>(&mut pc);
         };
         match pushed {
             StorageTryPushOutput::Pushed => {
@@ -375,7 +424,7 @@ where
                 .channel
                 .0
                 .space_available
-                .wait_for(&mut pc, timeout)
+                .wait_for::<InnerData<T, S, CV>, M>(&mut pc, timeout)
                 .timed_out()
             {
                 return Err(Error::Timeout);
@@ -410,10 +459,39 @@ where
     }
 }
 
-impl<T, S> Clone for BaseSenderAsync<T, S>
+impl<T, S, M, CV> DataSender<T> for BaseSenderAsync<T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T> + std::marker::Send,
+    M: RawMutexTrait + std::marker::Send + Sync,
+    CV: RawCondvar<RawMutex = M> + std::marker::Send + Sync,
+{
+    fn send(&self, value: T) -> Result<()> {
+        self.send_blocking(value)
+    }
+    fn send_timeout(&self, value: T, timeout: Duration) -> Result<()> {
+        self.send_blocking_timeout(value, timeout)
+    }
+    fn try_send(&self, value: T) -> Result<()> {
+        self.try_send(value)
+    }
+    fn send_async<'a>(&'a self, value: T) -> BoxFuture<'a, Result<()>>
+    where
+        T: std::marker::Send + 'a,
+    {
+        Box::pin(self.send(value))
+    }
+    fn is_alive(&self) -> bool {
+        self.is_alive()
+    }
+}
+
+impl<T, S, M, CV> Clone for BaseSenderAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     fn clone(&self) -> Self {
         self.channel.0.data.lock().senders += 1;
@@ -423,10 +501,12 @@ where
     }
 }
 
-impl<T, S> Drop for BaseSenderAsync<T, S>
+impl<T, S, M, CV> Drop for BaseSenderAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     fn drop(&mut self) {
         let mut pc = self.channel.0.data.lock();
@@ -437,13 +517,15 @@ where
     }
 }
 
-struct Recv<'a, T: Sized, S: ChannelStorage<T>> {
+struct Recv<'a, T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>> {
     id: usize,
-    channel: &'a BaseChannelAsync<T, S>,
+    channel: &'a BaseChannelAsync<T, S, M, CV>,
     queued: bool,
 }
 
-impl<T: Sized, S: ChannelStorage<T>> Drop for Recv<'_, T, S> {
+impl<T: Sized, S: ChannelStorage<T>, M: RawMutexTrait, CV: RawCondvar<RawMutex = M>> Drop
+    for Recv<'_, T, S, M, CV>
+{
     fn drop(&mut self) {
         if self.queued {
             self.channel.0.data.lock().notify_recv_fut_drop(self.id);
@@ -451,10 +533,12 @@ impl<T: Sized, S: ChannelStorage<T>> Drop for Recv<'_, T, S> {
     }
 }
 
-impl<T, S> Future for Recv<'_, T, S>
+impl<T, S, M, CV> Future for Recv<'_, T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     type Output = Result<T>;
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
@@ -480,18 +564,22 @@ where
 
 /// Base async receiver
 #[derive(Eq, PartialEq)]
-pub struct BaseReceiverAsync<T, S>
+pub struct BaseReceiverAsync<T, S, M = RawMutex, CV = Condvar>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
-    pub(crate) channel: BaseChannelAsync<T, S>,
+    pub(crate) channel: BaseChannelAsync<T, S, M, CV>,
 }
 
-impl<T, S> BaseReceiverAsync<T, S>
+impl<T, S, M, CV> BaseReceiverAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     /// Receives a value from the channel
     #[inline]
@@ -502,6 +590,12 @@ where
             queued: false,
         }
     }
+    /// Receives a value from the channel with the given timeout. The timeout is handled by the
+    /// shared [`crate::timer`] thread, so the method does not depend on any async runtime
+    pub fn recv_timeout(&self, timeout: Duration) -> impl Future<Output = Result<T>> + '_ {
+        let fut = timer::timeout(timeout, self.recv());
+        async move { fut.await? }
+    }
     /// Tries to receive a value from the channel
     pub fn try_recv(&self) -> Result<T> {
         let mut pc = self.channel.0.data.lock();
@@ -514,6 +608,46 @@ where
             Err(Error::ChannelEmpty)
         }
     }
+    /// Calls the function with a reference to the next value without receiving it
+    /// (non-blocking). Requires the channel storage to support peeking
+    pub fn peek_with<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let pc = self.channel.0.data.lock();
+        if let Some(val) = pc.queue.peek()? {
+            Ok(f(val))
+        } else if pc.senders == 0 {
+            Err(Error::ChannelClosed)
+        } else {
+            Err(Error::ChannelEmpty)
+        }
+    }
+    /// Retains only the values specified by the predicate, returns the number of removed values.
+    /// Pending senders are woken up if space has been freed. Requires the channel storage to
+    /// support retaining
+    pub fn retain<F>(&self, f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut pc = self.channel.0.data.lock();
+        let removed = pc.queue.retain(f)?;
+        for _ in 0..removed {
+            pc.notify_data_received();
+        }
+        Ok(removed)
+    }
+    /// Removes all values from the channel, returns the number of removed values. Pending
+    /// senders are woken up if space has been freed. Requires the channel storage to support
+    /// clearing
+    pub fn clear(&self) -> Result<usize> {
+        let mut pc = self.channel.0.data.lock();
+        let removed = pc.queue.clear()?;
+        for _ in 0..removed {
+            pc.notify_data_received();
+        }
+        Ok(removed)
+    }
     /// Receives a value from the channel in a blocking (synchronous) way
     pub fn recv_blocking(&self) -> Result<T> {
         let mut pc = self.channel.0.data.lock();
@@ -525,7 +659,10 @@ where
                 return Err(Error::ChannelClosed);
             }
             pc.append_recv_sync_waker();
-            self.channel.0.data_available.wait(&mut pc);
+            self.channel
+                .0
+                .data_available
+                .wait::<InnerData<T, S, CV>, M>(&mut pc);
         }
     }
     /// Receives a value from the channel in a blocking (synchronous) way with a given timeout
@@ -543,7 +680,7 @@ where
                 .channel
                 .0
                 .data_available
-                .wait_for(&mut pc, timeout)
+                .wait_for::<InnerData<T, S, CV>, M>(&mut pc, timeout)
                 .timed_out()
             {
                 return Err(Error::Timeout);
@@ -572,10 +709,39 @@ where
     }
 }
 
-impl<T, S> Clone for BaseReceiverAsync<T, S>
+impl<T, S, M, CV> DataReceiver<T> for BaseReceiverAsync<T, S, M, CV>
+where
+    T: Sized,
+    S: ChannelStorage<T> + std::marker::Send,
+    M: RawMutexTrait + std::marker::Send + Sync,
+    CV: RawCondvar<RawMutex = M> + std::marker::Send + Sync,
+{
+    fn recv(&self) -> Result<T> {
+        self.recv_blocking()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        self.recv_blocking_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<T> {
+        self.try_recv()
+    }
+    fn recv_async<'a>(&'a self) -> BoxFuture<'a, Result<T>>
+    where
+        T: std::marker::Send + 'a,
+    {
+        Box::pin(self.recv())
+    }
+    fn is_alive(&self) -> bool {
+        self.is_alive()
+    }
+}
+
+impl<T, S, M, CV> Clone for BaseReceiverAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     fn clone(&self) -> Self {
         self.channel.0.data.lock().receivers += 1;
@@ -585,10 +751,12 @@ where
     }
 }
 
-impl<T, S> Drop for BaseReceiverAsync<T, S>
+impl<T, S, M, CV> Drop for BaseReceiverAsync<T, S, M, CV>
 where
     T: Sized,
     S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
 {
     fn drop(&mut self) {
         let mut pc = self.channel.0.data.lock();
@@ -599,9 +767,16 @@ where
     }
 }
 
-pub(crate) fn make_channel<T: Sized, S: ChannelStorage<T>>(
-    ch: BaseChannelAsync<T, S>,
-) -> (BaseSenderAsync<T, S>, BaseReceiverAsync<T, S>) {
+#[allow(clippy::type_complexity)]
+pub(crate) fn make_channel<T, S, M, CV>(
+    ch: BaseChannelAsync<T, S, M, CV>,
+) -> (BaseSenderAsync<T, S, M, CV>, BaseReceiverAsync<T, S, M, CV>)
+where
+    T: Sized,
+    S: ChannelStorage<T>,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
     let tx = BaseSenderAsync {
         channel: ch.clone(),
     };
@@ -1,6 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use crate::{
+    base_channel::{BoxFuture, DataReceiver, DataSender},
     condvar_api::RawCondvar,
     locking::{Condvar, RawMutex},
     Error, Result,
@@ -81,11 +82,19 @@ where
         let value = self.inner.value.lock();
         value.closed
     }
-    /// Sets the primary value
+    /// Sets the primary value. The value is dropped if the cell is closed
     pub fn set(&self, data: P) {
+        let _ = self.try_set(data);
+    }
+    /// Sets the primary value. Returns [`Error::ChannelClosed`] if the cell is closed
+    pub fn try_set(&self, data: P) -> Result<()> {
         let mut value = self.inner.value.lock();
+        if value.closed {
+            return Err(Error::ChannelClosed);
+        }
         value.primary = Some(data);
         self.inner.data_available.notify_one();
+        Ok(())
     }
     /// Replaces the primary value and returns the old one if any
     pub fn replace(&self, data: P) -> Option<P> {
@@ -150,6 +159,50 @@ where
     }
 }
 
+impl<P, S, M, CV> DataSender<P> for Coupler<P, S, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn send(&self, value: P) -> Result<()> {
+        self.try_set(value)
+    }
+    fn send_timeout(&self, value: P, _timeout: Duration) -> Result<()> {
+        self.try_set(value)
+    }
+    fn try_send(&self, value: P) -> Result<()> {
+        self.try_set(value)
+    }
+    fn send_async<'a>(&'a self, value: P) -> BoxFuture<'a, Result<()>>
+    where
+        P: Send + 'a,
+    {
+        Box::pin(std::future::ready(self.try_set(value)))
+    }
+    fn is_alive(&self) -> bool {
+        !self.is_closed()
+    }
+}
+
+impl<P, S, M, CV> DataReceiver<(P, Option<S>)> for Coupler<P, S, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn recv(&self) -> Result<(P, Option<S>)> {
+        self.get()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<(P, Option<S>)> {
+        self.get_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<(P, Option<S>)> {
+        self.try_get()
+    }
+    fn is_alive(&self) -> bool {
+        !self.is_closed()
+    }
+}
+
 impl<P, S> Iterator for Coupler<P, S> {
     type Item = (P, Option<S>);
     fn next(&mut self) -> Option<Self::Item> {
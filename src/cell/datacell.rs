@@ -1,4 +1,5 @@
 use crate::{
+    base_channel::{BoxFuture, DataReceiver, DataSender},
     condvar_api::RawCondvar,
     locking::{Condvar, RawMutex},
     Error, Result,
@@ -78,11 +79,19 @@ where
         let value = self.inner.value.lock();
         value.closed
     }
-    /// Sets the data in the cell
+    /// Sets the data in the cell. The value is dropped if the cell is closed
     pub fn set(&self, data: P) {
+        let _ = self.try_set(data);
+    }
+    /// Sets the data in the cell. Returns [`Error::ChannelClosed`] if the cell is closed
+    pub fn try_set(&self, data: P) -> Result<()> {
         let mut value = self.inner.value.lock();
+        if value.closed {
+            return Err(Error::ChannelClosed);
+        }
         value.current = Some(data);
         self.inner.data_available.notify_one();
+        Ok(())
     }
     /// Replaces the value in the cell and returns the old one if any
     pub fn replace(&self, data: P) -> Option<P> {
@@ -136,6 +145,50 @@ where
     }
 }
 
+impl<P, M, CV> DataSender<P> for DataCell<P, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn send(&self, value: P) -> Result<()> {
+        self.try_set(value)
+    }
+    fn send_timeout(&self, value: P, _timeout: Duration) -> Result<()> {
+        self.try_set(value)
+    }
+    fn try_send(&self, value: P) -> Result<()> {
+        self.try_set(value)
+    }
+    fn send_async<'a>(&'a self, value: P) -> BoxFuture<'a, Result<()>>
+    where
+        P: Send + 'a,
+    {
+        Box::pin(std::future::ready(self.try_set(value)))
+    }
+    fn is_alive(&self) -> bool {
+        !self.is_closed()
+    }
+}
+
+impl<P, M, CV> DataReceiver<P> for DataCell<P, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn recv(&self) -> Result<P> {
+        self.get()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<P> {
+        self.get_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<P> {
+        self.try_get()
+    }
+    fn is_alive(&self) -> bool {
+        !self.is_closed()
+    }
+}
+
 impl<P, M, CV> Iterator for DataCell<P, M, CV>
 where
     M: RawMutexTrait,
@@ -178,6 +231,11 @@ mod test {
         cell.close();
         assert!(matches!(cell.get().unwrap_err(), Error::ChannelClosed));
         handle.join().unwrap();
+        assert!(matches!(cell.try_set(1).unwrap_err(), Error::ChannelClosed));
+        assert!(matches!(
+            crate::DataSender::send(&cell, 1).unwrap_err(),
+            Error::ChannelClosed
+        ));
     }
 
     #[test]
@@ -4,7 +4,9 @@ use crate::{
     base_channel::{make_channel, BaseChannel, BaseReceiver, BaseSender, ChannelStorage},
     condvar_api::RawCondvar,
     data_policy::StorageTryPushOutput,
+    growable,
     locking::{Condvar, RawMutex},
+    Result,
 };
 
 impl<T> ChannelStorage<T> for VecDeque<T>
@@ -43,6 +45,75 @@ where
     fn is_empty(&self) -> bool {
         Self::is_empty(self)
     }
+
+    fn peek(&self) -> Result<Option<&T>> {
+        Ok(self.front())
+    }
+
+    fn retain<F>(&mut self, f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = Self::len(self);
+        Self::retain(self, f);
+        Ok(len - Self::len(self))
+    }
+
+    fn clear(&mut self) -> Result<usize> {
+        let len = Self::len(self);
+        Self::clear(self);
+        Ok(len)
+    }
+}
+
+impl<T> ChannelStorage<T> for growable::Deque<T>
+where
+    T: Sized,
+{
+    fn with_capacity_and_ordering(capacity: usize, ordering: bool) -> Self
+    where
+        Self: Sized,
+    {
+        assert!(!ordering, "Ordering is not supported for growable::Deque");
+        growable::Deque::bounded(capacity)
+    }
+
+    fn try_push(&mut self, value: T) -> StorageTryPushOutput<T> {
+        Self::try_push(self, value)
+    }
+
+    fn get(&mut self) -> Option<T> {
+        Self::get(self)
+    }
+
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+
+    fn is_full(&self) -> bool {
+        Self::is_full(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        Self::is_empty(self)
+    }
+
+    fn peek(&self) -> Result<Option<&T>> {
+        Ok(Self::peek(self))
+    }
+
+    fn retain<F>(&mut self, f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        Ok(Self::retain(self, f))
+    }
+
+    fn clear(&mut self) -> Result<usize> {
+        let len = Self::len(self);
+        Self::clear(self);
+        Ok(len)
+    }
 }
 
 /// Channel sender
@@ -97,6 +168,129 @@ where
     (tx, rx)
 }
 
+/// Growable (unbounded/soft-bounded) channel sender
+pub type GrowableSender<T, M, CV> = BaseSender<T, growable::Deque<T>, M, CV>;
+
+/// Growable channel default platform sender
+pub type DefaultGrowableSender<T> = GrowableSender<T, RawMutex, Condvar>;
+
+/// Growable (unbounded/soft-bounded) channel receiver
+pub type GrowableReceiver<T, M, CV> = BaseReceiver<T, growable::Deque<T>, M, CV>;
+
+/// Growable channel default platform receiver
+pub type DefaultGrowableReceiver<T> = GrowableReceiver<T, RawMutex, Condvar>;
+
+/// Unbounded channel structure. Used to be destructurized into a sender and a receiver. A
+/// workaround to let the user use the default Mutex and Condvar types if others are not required
+///
+/// Unbounded channels are not real-time safe as memory is allocated on demand, use them for
+/// non-real-time side paths (logging, diagnostics) only
+pub struct Unbounded<T, M = RawMutex, CV = Condvar>
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    /// Channel sender
+    pub tx: GrowableSender<T, M, CV>,
+    /// Channel receiver
+    pub rx: GrowableReceiver<T, M, CV>,
+}
+
+impl<T, M, CV> Unbounded<T, M, CV>
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    /// Create a new unbounded channel
+    pub fn new() -> Self {
+        let ch = BaseChannel::with_storage(growable::Deque::unbounded());
+        let (tx, rx) = make_channel(ch);
+        Self { tx, rx }
+    }
+}
+
+impl<T, M, CV> Default for Unbounded<T, M, CV>
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Soft-bounded channel structure. Used to be destructurized into a sender and a receiver. A
+/// workaround to let the user use the default Mutex and Condvar types if others are not required
+///
+/// The channel storage grows on demand up to the hard limit and reports a warning once its
+/// length passes the soft watermark
+///
+/// # Panics
+///
+/// Will panic if the soft limit is zero or greater than the hard limit
+pub struct SoftBounded<T, M = RawMutex, CV = Condvar>
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    /// Channel sender
+    pub tx: GrowableSender<T, M, CV>,
+    /// Channel receiver
+    pub rx: GrowableReceiver<T, M, CV>,
+}
+
+impl<T, M, CV> SoftBounded<T, M, CV>
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    /// Create a new soft-bounded channel
+    pub fn new(soft_limit: usize, hard_limit: usize) -> Self {
+        let ch = BaseChannel::with_storage(
+            growable::Deque::bounded(hard_limit).set_soft_limit(soft_limit),
+        );
+        let (tx, rx) = make_channel(ch);
+        Self { tx, rx }
+    }
+}
+
+/// Create an unbounded channel and return it as a tuple of a sender and a receiver
+pub fn unbounded<T, M, CV>() -> (GrowableSender<T, M, CV>, GrowableReceiver<T, M, CV>)
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    let Unbounded { tx, rx } = Unbounded::new();
+    (tx, rx)
+}
+
+/// Create a soft-bounded channel and return it as a tuple of a sender and a receiver
+///
+/// # Panics
+///
+/// Will panic if the soft limit is zero or greater than the hard limit
+pub fn soft_bounded<T, M, CV>(
+    soft_limit: usize,
+    hard_limit: usize,
+) -> (GrowableSender<T, M, CV>, GrowableReceiver<T, M, CV>)
+where
+    M: lock_api::RawMutex,
+    CV: RawCondvar,
+{
+    let SoftBounded { tx, rx } = SoftBounded::new(soft_limit, hard_limit);
+    (tx, rx)
+}
+
+/// Create a new unbounded channel and automatically destructurize it into a sender and a receiver
+#[allow(clippy::module_name_repetitions)]
+#[macro_export]
+macro_rules! channel_unbounded {
+    () => {{
+        let $crate::channel::Unbounded { tx, rx } = $crate::channel::Unbounded::<_>::new();
+        (tx, rx)
+    }};
+}
+
 /// Create a new bounded channel and automatically destructurize it into a sender and a receiver
 #[allow(clippy::module_name_repetitions)]
 #[macro_export]
@@ -196,4 +390,102 @@ mod test {
         assert!(tx.is_empty());
         assert!(rx.is_empty());
     }
+
+    #[test]
+    fn test_peek_retain_clear() {
+        let (tx, rx) = channel_bounded!(4);
+        for i in 0..4 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(rx.peek_with(|v| *v).unwrap(), 0);
+        assert_eq!(rx.len(), 4);
+        let tx_t = tx.clone();
+        let handle = thread::spawn(move || {
+            tx_t.send(4).unwrap();
+            tx_t.send(5).unwrap();
+        });
+        thread::sleep(Duration::from_millis(10));
+        // remove odd values, the blocked sender must be woken up
+        assert_eq!(rx.retain(|v| v % 2 == 0).unwrap(), 2);
+        handle.join().unwrap();
+        assert_eq!(rx.len(), 4);
+        assert_eq!(rx.recv().unwrap(), 0);
+        assert_eq!(rx.clear().unwrap(), 3);
+        assert!(matches!(
+            rx.peek_with(|v| *v).unwrap_err(),
+            crate::Error::ChannelEmpty
+        ));
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let (tx, rx) = crate::channel_unbounded!();
+        for i in 0..10_000 {
+            tx.try_send(i).unwrap();
+        }
+        assert!(!tx.is_full());
+        drop(tx);
+        assert_eq!(rx.count(), 10_000);
+    }
+
+    #[test]
+    fn test_soft_bounded() {
+        let super::SoftBounded { tx, rx }: super::SoftBounded<i32> = super::SoftBounded::new(2, 4);
+        for i in 0..4 {
+            tx.send(i).unwrap();
+        }
+        assert!(matches!(tx.try_send(4), Err(crate::Error::ChannelFull)));
+        assert_eq!(rx.recv().unwrap(), 0);
+        tx.send(4).unwrap();
+        assert_eq!(rx.len(), 4);
+    }
+
+    #[test]
+    fn test_receiver_dropped() {
+        let (tx, rx) = channel_bounded!(1);
+        tx.send(1).unwrap();
+        let handle = thread::spawn(move || tx.send(2));
+        thread::sleep(Duration::from_millis(10));
+        drop(rx);
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(crate::Error::ChannelClosed)
+        ));
+    }
+
+    #[test]
+    fn test_dyn_transports() {
+        use crate::{cell::DataCell, channel_async, rendezvous, DataReceiver, DataSender, Error};
+
+        type Transport = (
+            Box<dyn DataSender<u32> + Send + Sync>,
+            Box<dyn DataReceiver<u32> + Send + Sync>,
+        );
+        let cell: DataCell<u32> = DataCell::new();
+        let (tx_a, rx_a) = channel_async::bounded::<u32>(4);
+        let (tx_s, rx_s) = channel_bounded!(4);
+        let transports: Vec<Transport> = vec![
+            (Box::new(tx_s), Box::new(rx_s)),
+            (Box::new(tx_a), Box::new(rx_a)),
+            (Box::new(cell.clone()), Box::new(cell)),
+        ];
+        for (tx, rx) in transports {
+            assert!(matches!(rx.try_recv(), Err(Error::ChannelEmpty)));
+            tx.send(1).unwrap();
+            tx.try_send(2).unwrap();
+            tx.send_timeout(3, Duration::from_millis(10)).unwrap();
+            let mut received = vec![rx.recv().unwrap()];
+            while let Ok(v) = rx.recv_timeout(Duration::from_millis(10)) {
+                received.push(v);
+            }
+            assert_eq!(received.last(), Some(&3));
+            assert!(DataSender::is_alive(&tx));
+        }
+        let rendezvous::Rendezvous { tx, rx } = rendezvous::Rendezvous::<u32>::new();
+        let tx: Box<dyn DataSender<u32> + Send + Sync> = Box::new(tx);
+        let rx: Box<dyn DataReceiver<u32> + Send + Sync> = Box::new(rx);
+        assert!(matches!(tx.try_send(1), Err(Error::ChannelFull)));
+        thread::spawn(move || tx.send(42).unwrap());
+        assert_eq!(rx.recv().unwrap(), 42);
+    }
 }
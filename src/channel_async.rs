@@ -1,14 +1,47 @@
 use std::collections::VecDeque;
 
-use crate::base_channel_async::{
-    make_channel, BaseChannelAsync, BaseReceiverAsync, BaseSenderAsync,
+use crate::{
+    base_channel_async::{make_channel, BaseChannelAsync, BaseReceiverAsync, BaseSenderAsync},
+    condvar_api::RawCondvar,
+    growable,
+    locking::{Condvar, RawMutex},
 };
+use lock_api::RawMutex as RawMutexTrait;
 
 /// Channel sender
-pub type Sender<T> = BaseSenderAsync<T, VecDeque<T>>;
+pub type Sender<T, M = RawMutex, CV = Condvar> = BaseSenderAsync<T, VecDeque<T>, M, CV>;
 
 /// Channel receiver
-pub type Receiver<T> = BaseReceiverAsync<T, VecDeque<T>>;
+pub type Receiver<T, M = RawMutex, CV = Condvar> = BaseReceiverAsync<T, VecDeque<T>, M, CV>;
+
+/// Bounded async channel structure. Used to be destructurized into a sender and a receiver. Allows
+/// to specify custom Mutex and Condvar types
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+pub struct Bounded<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Channel sender
+    pub tx: Sender<T, M, CV>,
+    /// Channel receiver
+    pub rx: Receiver<T, M, CV>,
+}
+
+impl<T, M, CV> Bounded<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Creates a new bounded async channel
+    pub fn new(capacity: usize) -> Self {
+        let (tx, rx) = make_channel(BaseChannelAsync::new(capacity, false));
+        Self { tx, rx }
+    }
+}
 
 /// Create a new bounded async channel
 ///
@@ -16,15 +49,113 @@ pub type Receiver<T> = BaseReceiverAsync<T, VecDeque<T>>;
 ///
 /// Will panic if the capacity is zero
 pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
-    let ch = BaseChannelAsync::new(capacity, false);
-    make_channel(ch)
+    let Bounded { tx, rx } = Bounded::new(capacity);
+    (tx, rx)
+}
+
+/// Growable (unbounded/soft-bounded) channel sender
+pub type GrowableSender<T, M = RawMutex, CV = Condvar> =
+    BaseSenderAsync<T, growable::Deque<T>, M, CV>;
+
+/// Growable (unbounded/soft-bounded) channel receiver
+pub type GrowableReceiver<T, M = RawMutex, CV = Condvar> =
+    BaseReceiverAsync<T, growable::Deque<T>, M, CV>;
+
+/// Unbounded async channel structure. Used to be destructurized into a sender and a receiver.
+/// Allows to specify custom Mutex and Condvar types
+pub struct Unbounded<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Channel sender
+    pub tx: GrowableSender<T, M, CV>,
+    /// Channel receiver
+    pub rx: GrowableReceiver<T, M, CV>,
+}
+
+impl<T, M, CV> Unbounded<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Creates a new unbounded async channel
+    pub fn new() -> Self {
+        let (tx, rx) = make_channel(BaseChannelAsync::with_storage(growable::Deque::unbounded()));
+        Self { tx, rx }
+    }
+}
+
+impl<T, M, CV> Default for Unbounded<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Soft-bounded async channel structure. Used to be destructurized into a sender and a receiver.
+/// Allows to specify custom Mutex and Condvar types
+///
+/// # Panics
+///
+/// Will panic if the soft limit is zero or greater than the hard limit
+pub struct SoftBounded<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Channel sender
+    pub tx: GrowableSender<T, M, CV>,
+    /// Channel receiver
+    pub rx: GrowableReceiver<T, M, CV>,
+}
+
+impl<T, M, CV> SoftBounded<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Creates a new soft-bounded async channel
+    pub fn new(soft_limit: usize, hard_limit: usize) -> Self {
+        let (tx, rx) = make_channel(BaseChannelAsync::with_storage(
+            growable::Deque::bounded(hard_limit).set_soft_limit(soft_limit),
+        ));
+        Self { tx, rx }
+    }
+}
+
+/// Create a new unbounded async channel
+///
+/// Unbounded channels are not real-time safe as memory is allocated on demand, use them for
+/// non-real-time side paths (logging, diagnostics) only
+pub fn unbounded<T>() -> (GrowableSender<T>, GrowableReceiver<T>) {
+    let Unbounded { tx, rx } = Unbounded::new();
+    (tx, rx)
+}
+
+/// Create a new soft-bounded async channel. The channel storage grows on demand up to the hard
+/// limit and reports a warning once its length passes the soft watermark
+///
+/// # Panics
+///
+/// Will panic if the soft limit is zero or greater than the hard limit
+pub fn soft_bounded<T>(
+    soft_limit: usize,
+    hard_limit: usize,
+) -> (GrowableSender<T>, GrowableReceiver<T>) {
+    let SoftBounded { tx, rx } = SoftBounded::new(soft_limit, hard_limit);
+    (tx, rx)
 }
 
 #[cfg(test)]
 mod test {
     use std::time::Duration;
 
-    use super::bounded;
+    use super::{bounded, Bounded};
+    use crate::Error;
 
     #[tokio::test]
     async fn test_delivery() {
@@ -51,6 +182,58 @@ mod test {
         assert!(data.recv_fut_pending.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_custom_locking() {
+        let Bounded { tx, rx } =
+            Bounded::<u32, crate::spin::RawMutex, crate::spin::Condvar>::new(1);
+        let handle = std::thread::spawn(move || {
+            for i in 0..10 {
+                tx.send_blocking(i).unwrap();
+            }
+        });
+        for i in 0..10 {
+            assert_eq!(rx.recv().await.unwrap(), i);
+        }
+        handle.join().unwrap();
+        let Bounded { tx, rx } =
+            Bounded::<u32, parking_lot_rt::RawMutex, parking_lot_rt::Condvar>::new(1);
+        tx.send(1).await.unwrap();
+        assert_eq!(rx.recv_blocking().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeouts() {
+        let (tx, rx) = bounded::<u32>(1);
+        assert!(matches!(
+            rx.recv_timeout(Duration::from_millis(10)).await,
+            Err(Error::Timeout)
+        ));
+        tx.send_timeout(1, Duration::from_millis(10)).await.unwrap();
+        assert!(matches!(
+            tx.send_timeout(2, Duration::from_millis(10)).await,
+            Err(Error::Timeout)
+        ));
+        {
+            // the cancelled waiters are removed from the queues
+            let data = rx.channel.0.data.lock();
+            assert!(data.send_fut_wakers.is_empty());
+            assert!(data.send_fut_waker_ids.is_empty());
+            assert!(data.send_fut_pending.is_empty());
+        }
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)).await.unwrap(), 1);
+        let rx_c = rx.clone();
+        let pending =
+            tokio::spawn(async move { rx_c.recv_timeout(Duration::from_millis(10)).await });
+        let waiting = tokio::spawn(async move { rx.recv_timeout(Duration::from_secs(5)).await });
+        assert!(matches!(pending.await.unwrap(), Err(Error::Timeout)));
+        tx.send(3).await.unwrap();
+        assert_eq!(waiting.await.unwrap().unwrap(), 3);
+        let data = tx.channel.0.data.lock();
+        assert!(data.recv_fut_wakers.is_empty());
+        assert!(data.recv_fut_waker_ids.is_empty());
+        assert!(data.recv_fut_pending.is_empty());
+    }
+
     #[tokio::test]
     async fn test_tx_ordering() {
         let (tx, rx) = bounded(1);
@@ -108,4 +291,73 @@ mod test {
             assert!(rx_t.is_finished(), "RX poisined {}", i);
         }
     }
+
+    #[tokio::test]
+    async fn test_peek_retain_clear() {
+        let (tx, rx) = bounded(4);
+        for i in 0..4 {
+            tx.send(i).await.unwrap();
+        }
+        assert_eq!(rx.peek_with(|v| *v).unwrap(), 0);
+        let handle = tokio::spawn(async move {
+            tx.send(4).await.unwrap();
+            tx.send(5).await.unwrap();
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        // remove odd values, the pending sender must be woken up
+        assert_eq!(rx.retain(|v| v % 2 == 0).unwrap(), 2);
+        handle.await.unwrap();
+        assert_eq!(rx.len(), 4);
+        assert_eq!(rx.recv().await.unwrap(), 0);
+        assert_eq!(rx.clear().unwrap(), 3);
+        assert!(matches!(
+            rx.try_recv().unwrap_err(),
+            crate::Error::ChannelClosed
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unbounded() {
+        let (tx, rx) = super::unbounded();
+        for i in 0..10_000 {
+            tx.send(i).await.unwrap();
+        }
+        drop(tx);
+        let mut c = 0;
+        while rx.recv().await.is_ok() {
+            c += 1;
+        }
+        assert_eq!(c, 10_000);
+    }
+
+    #[tokio::test]
+    async fn test_dyn_async() {
+        use crate::{cell::DataCell, policy_channel_async, DataReceiver, DataSender, Error};
+
+        let (tx, rx) = bounded::<u32>(1);
+        let tx: Box<dyn DataSender<u32> + Send + Sync> = Box::new(tx);
+        let rx: Box<dyn DataReceiver<u32> + Send + Sync> = Box::new(rx);
+        tokio::spawn(async move {
+            for i in 0..10 {
+                tx.send_async(i).await.unwrap();
+            }
+        });
+        let mut received = Vec::new();
+        while let Ok(v) = rx.recv_async().await {
+            received.push(v);
+        }
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+        let (tx, rx) = policy_channel_async::bounded::<usize>(1);
+        drop(rx);
+        assert!(matches!(
+            DataSender::send_async(&tx, 1).await,
+            Err(Error::ChannelClosed)
+        ));
+        let cell: DataCell<u32> = DataCell::new();
+        DataSender::send_async(&cell, 1).await.unwrap();
+        assert!(matches!(
+            DataReceiver::recv_async(&cell).await,
+            Err(Error::Unimplemented)
+        ));
+    }
 }
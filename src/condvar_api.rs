@@ -98,3 +98,189 @@ impl RawCondvar for parking_lot::Condvar {
         self.notify_all();
     }
 }
+
+/// Conformance tests, executed for every locking backend
+#[cfg(test)]
+mod test {
+    macro_rules! conformance {
+        ($name: ident, $m: ty, $cv: ty) => {
+            conformance!($name, $m, $cv, 1_000);
+        };
+        // pure spin backends require a lower number of iterations on machines with few CPUs
+        ($name: ident, $m: ty, $cv: ty, $iters: expr) => {
+            mod $name {
+                use std::{sync::Arc, thread, time::Duration};
+
+                use crate::{
+                    cell::{Coupler, DataCell, TripleCoupler},
+                    channel, policy_channel,
+                    semaphore::Semaphore,
+                    Error,
+                };
+
+                const ITERS: usize = $iters;
+
+                #[test]
+                fn test_mutex() {
+                    let mutex: Arc<lock_api::Mutex<$m, usize>> = Arc::new(lock_api::Mutex::new(0));
+                    let handles: Vec<_> = (0..10)
+                        .map(|_| {
+                            let m = mutex.clone();
+                            thread::spawn(move || {
+                                for _ in 0..ITERS {
+                                    *m.lock() += 1;
+                                }
+                            })
+                        })
+                        .collect();
+                    for handle in handles {
+                        handle.join().unwrap();
+                    }
+                    assert_eq!(*mutex.lock(), 10 * ITERS);
+                    let _g = mutex.lock();
+                    thread::scope(|s| {
+                        s.spawn(|| assert!(mutex.try_lock().is_none()));
+                    });
+                }
+
+                #[test]
+                fn test_datacell() {
+                    let cell: DataCell<usize, $m, $cv> = DataCell::new();
+                    assert!(matches!(cell.try_get(), Err(Error::ChannelEmpty)));
+                    assert!(matches!(
+                        cell.get_timeout(Duration::from_millis(10)),
+                        Err(Error::Timeout)
+                    ));
+                    let cell2 = cell.clone();
+                    let handle = thread::spawn(move || {
+                        thread::sleep(Duration::from_millis(10));
+                        cell2.set(42);
+                    });
+                    assert_eq!(cell.get().unwrap(), 42);
+                    handle.join().unwrap();
+                    cell.close();
+                    assert!(matches!(cell.get(), Err(Error::ChannelClosed)));
+                }
+
+                #[test]
+                fn test_couplers() {
+                    let cell: Coupler<usize, usize, $m, $cv> = Coupler::new();
+                    cell.set_second(2);
+                    let cell2 = cell.clone();
+                    thread::spawn(move || {
+                        thread::sleep(Duration::from_millis(10));
+                        cell2.set(1);
+                    });
+                    assert_eq!(cell.get().unwrap(), (1, Some(2)));
+                    let cell: TripleCoupler<usize, usize, usize, $m, $cv> = TripleCoupler::new();
+                    cell.set_third(3);
+                    let cell2 = cell.clone();
+                    thread::spawn(move || {
+                        thread::sleep(Duration::from_millis(10));
+                        cell2.set(1);
+                    });
+                    assert_eq!(cell.get().unwrap(), (1, None, Some(3)));
+                }
+
+                #[test]
+                fn test_semaphore() {
+                    let sem: Arc<Semaphore<$m, $cv>> = Arc::new(Semaphore::new(2));
+                    let handles: Vec<_> = (0..10)
+                        .map(|_| {
+                            let sem = sem.clone();
+                            thread::spawn(move || {
+                                for _ in 0..ITERS / 10 {
+                                    let _g = sem.acquire();
+                                    assert!(sem.used() <= 2);
+                                }
+                            })
+                        })
+                        .collect();
+                    for handle in handles {
+                        handle.join().unwrap();
+                    }
+                    assert_eq!(sem.available(), 2);
+                }
+
+                #[test]
+                fn test_channel() {
+                    let (tx, rx) = channel::bounded::<usize, $m, $cv>(1);
+                    let handles: Vec<_> = (0..4)
+                        .map(|_| {
+                            let tx = tx.clone();
+                            thread::spawn(move || {
+                                for i in 0..ITERS {
+                                    tx.send(i).unwrap();
+                                }
+                            })
+                        })
+                        .collect();
+                    drop(tx);
+                    let mut sum = 0;
+                    while let Ok(v) = rx.recv() {
+                        sum += v;
+                    }
+                    assert_eq!(sum, 4 * ITERS * (ITERS - 1) / 2);
+                    for handle in handles {
+                        handle.join().unwrap();
+                    }
+                    let (tx, rx) = channel::bounded::<usize, $m, $cv>(1);
+                    assert!(matches!(
+                        rx.recv_timeout(Duration::from_millis(10)),
+                        Err(Error::Timeout)
+                    ));
+                    tx.send(1).unwrap();
+                    assert!(matches!(
+                        tx.send_timeout(2, Duration::from_millis(10)),
+                        Err(Error::Timeout)
+                    ));
+                    drop(rx);
+                    assert!(matches!(tx.send(3), Err(Error::ChannelClosed)));
+                }
+
+                #[test]
+                fn test_policy_channel() {
+                    let (tx, rx) = policy_channel::ordered::<usize, $m, $cv>(16);
+                    let handle = thread::spawn(move || {
+                        for i in 0..ITERS {
+                            tx.send(i).unwrap();
+                        }
+                    });
+                    let mut received = 0;
+                    while rx.recv().is_ok() {
+                        received += 1;
+                    }
+                    assert_eq!(received, ITERS);
+                    handle.join().unwrap();
+                }
+            }
+        };
+    }
+
+    #[cfg(target_os = "linux")]
+    conformance!(pi, crate::pi::RawMutex, crate::pi::Condvar);
+    conformance!(
+        parking_lot_rt,
+        parking_lot_rt::RawMutex,
+        parking_lot_rt::Condvar
+    );
+    #[cfg(feature = "parking_lot")]
+    conformance!(parking_lot, parking_lot::RawMutex, parking_lot::Condvar);
+    conformance!(
+        spin_hybrid,
+        crate::spin::HybridLock,
+        crate::spin::HybridCondvar
+    );
+    conformance!(
+        spin_hybrid_no_spins,
+        crate::spin::HybridLock<0>,
+        crate::spin::HybridCondvar<0>
+    );
+    conformance!(
+        spin_pure,
+        crate::spin::SpinLock,
+        crate::spin::SpinCondvar,
+        20
+    );
+    conformance!(std_sync, crate::std_sync::StdLock, crate::std_sync::Condvar);
+}
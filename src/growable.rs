@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+
+use tracing::warn;
+
+use crate::data_policy::StorageTryPushOutput;
+
+/// A deque which grows on demand up to an optional hard limit and reports a warning once its
+/// length passes an optional soft watermark
+#[derive(Clone, Debug)]
+pub struct Deque<T> {
+    data: VecDeque<T>,
+    soft_limit: Option<usize>,
+    hard_limit: Option<usize>,
+    above_soft_limit: bool,
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::unbounded()
+    }
+}
+
+impl<T> Deque<T> {
+    /// Creates a new unbounded deque
+    #[inline]
+    pub fn unbounded() -> Self {
+        Self {
+            data: VecDeque::new(),
+            soft_limit: None,
+            hard_limit: None,
+            above_soft_limit: false,
+        }
+    }
+    /// Creates a new deque which is limited by the hard limit. Memory is allocated on demand
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the hard limit is zero
+    #[inline]
+    pub fn bounded(hard_limit: usize) -> Self {
+        assert!(hard_limit > 0, "hard limit MUST be > 0");
+        Self {
+            hard_limit: Some(hard_limit),
+            ..Self::unbounded()
+        }
+    }
+    /// Sets the soft watermark. When the deque length passes it, a warning is logged with
+    /// [`tracing::warn!`]. The warning is repeated only after the length drops below the
+    /// watermark. Can be used as a build pattern
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the soft watermark is zero or greater than the hard limit
+    #[inline]
+    pub fn set_soft_limit(mut self, soft_limit: usize) -> Self {
+        assert!(soft_limit > 0, "soft limit MUST be > 0");
+        if let Some(hard_limit) = self.hard_limit {
+            assert!(soft_limit <= hard_limit, "soft limit MUST be <= hard limit");
+        }
+        self.soft_limit = Some(soft_limit);
+        self
+    }
+    /// Tries to store the value. Returns the value back if the hard limit is reached
+    pub fn try_push(&mut self, value: T) -> StorageTryPushOutput<T> {
+        if self.is_full() {
+            return StorageTryPushOutput::Full(value);
+        }
+        self.data.push_back(value);
+        if let Some(soft_limit) = self.soft_limit {
+            if self.data.len() > soft_limit && !self.above_soft_limit {
+                self.above_soft_limit = true;
+                warn!(
+                    len = self.data.len(),
+                    soft_limit, "growable deque passed the soft limit"
+                );
+            }
+        }
+        StorageTryPushOutput::Pushed
+    }
+    /// Returns the first available value
+    #[inline]
+    pub fn get(&mut self) -> Option<T> {
+        let value = self.data.pop_front();
+        self.check_soft_limit();
+        value
+    }
+    /// Returns a reference to the first available value
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.front()
+    }
+    /// Retains only the values specified by the predicate. Returns the number of removed values
+    #[inline]
+    pub fn retain<F>(&mut self, f: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.data.len();
+        self.data.retain(f);
+        self.check_soft_limit();
+        len - self.data.len()
+    }
+    /// Clears the deque
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.above_soft_limit = false;
+    }
+    /// Returns number of elements in deque
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    /// Returns is the deque full (always false for unbounded deques)
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.hard_limit.is_some_and(|l| self.data.len() >= l)
+    }
+    /// Returns is the deque empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Returns the soft watermark
+    #[inline]
+    pub fn soft_limit(&self) -> Option<usize> {
+        self.soft_limit
+    }
+    /// Returns the hard limit
+    #[inline]
+    pub fn hard_limit(&self) -> Option<usize> {
+        self.hard_limit
+    }
+    #[inline]
+    fn check_soft_limit(&mut self) {
+        if self.above_soft_limit && self.soft_limit.is_some_and(|l| self.data.len() < l) {
+            self.above_soft_limit = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Deque;
+    use crate::data_policy::StorageTryPushOutput;
+
+    #[test]
+    fn test_soft_bounded() {
+        let mut d: Deque<usize> = Deque::bounded(4).set_soft_limit(2);
+        for i in 0..4 {
+            assert!(matches!(d.try_push(i), StorageTryPushOutput::Pushed));
+        }
+        assert!(d.above_soft_limit);
+        assert!(d.is_full());
+        assert!(matches!(d.try_push(4), StorageTryPushOutput::Full(4)));
+        d.get();
+        d.get();
+        d.get();
+        assert!(!d.above_soft_limit);
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let mut d: Deque<usize> = Deque::unbounded();
+        for i in 0..10_000 {
+            assert!(matches!(d.try_push(i), StorageTryPushOutput::Pushed));
+        }
+        assert!(!d.is_full());
+        assert_eq!(d.len(), 10_000);
+    }
+}
@@ -1,5 +1,7 @@
 #![ doc = include_str!( concat!( env!( "CARGO_MANIFEST_DIR" ), "/", "README.md" ) ) ]
 #![deny(missing_docs)]
+/// Channel adapters
+pub mod adapter;
 /// Data buffer
 pub mod buf;
 /// Cell synchronization
@@ -12,6 +14,8 @@ pub mod channel_async;
 pub mod data_policy;
 /// Event map
 pub mod event_map;
+/// Growable deque
+pub mod growable;
 /// Priority-inverting-safe locking (Linux only)
 #[cfg(target_os = "linux")]
 pub mod pi;
@@ -27,6 +31,8 @@ pub mod pi {
     /// Re-exports parking_lot_rt MutexGuard
     pub type MutexGuard<'a, T> = parking_lot_rt::MutexGuard<'a, T>;
 }
+/// Oneshot channel
+pub mod oneshot;
 /// Policy-based sync channel
 pub mod policy_channel;
 /// Policy-based async channel
@@ -35,12 +41,30 @@ pub mod policy_channel_async;
 pub use parking_lot_rt as locking;
 #[cfg(target_os = "linux")]
 pub use pi as locking;
+/// Rendezvous (zero-capacity) channel
+pub mod rendezvous;
+/// Request/response channel
+pub mod rpc;
+/// Periodic task scheduler (cyclic executive)
+pub mod scheduler;
 /// Semaphore
 pub mod semaphore;
+/// Spin-based locking
+pub mod spin;
+/// Locking, based on the standard library synchronization primitives
+pub mod std_sync;
 /// System tools
 pub mod system;
+/// Conformance checks for custom components (requires `testing` feature)
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 /// Time tools
 pub mod time;
+/// Runtime-agnostic async timers
+pub mod timer;
+/// timerfd-based periodic timer (Linux only)
+#[cfg(target_os = "linux")]
+pub mod timerfd;
 /// Timestamps
 pub use bma_ts;
 /// Base channel type, allows to build sync channels with a custom storage
@@ -55,8 +79,10 @@ pub mod ops;
 pub mod pdeque;
 /// Thread scheduling
 pub mod thread_rt;
+/// Watchdog for real-time tasks
+pub mod watchdog;
 
-pub use base_channel::DataChannel;
+pub use base_channel::{DataChannel, DataReceiver, DataSender};
 
 pub use rtsc_derive::DataPolicy;
 
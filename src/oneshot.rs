@@ -0,0 +1,320 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{
+    base_channel::{BoxFuture, DataReceiver},
+    condvar_api::RawCondvar,
+    locking::{Condvar, RawMutex},
+    ops::Operation,
+    Error, Result,
+};
+use lock_api::RawMutex as RawMutexTrait;
+
+struct State<T> {
+    value: Option<T>,
+    // the value has been sent or the sender has been dropped
+    completed: bool,
+    receiver_alive: bool,
+    waker: Option<Waker>,
+}
+
+struct OneshotInner<T, M, CV> {
+    state: lock_api::Mutex<M, State<T>>,
+    data_available: CV,
+}
+
+impl<T, M, CV> OneshotInner<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn complete(&self, value: Option<T>) -> std::result::Result<(), Option<T>> {
+        let mut state = self.state.lock();
+        if state.completed {
+            return Ok(());
+        }
+        if !state.receiver_alive {
+            state.completed = true;
+            return Err(value);
+        }
+        state.value = value;
+        state.completed = true;
+        self.data_available.notify_all();
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+    fn recv(&self, op: Option<Operation>) -> Result<T> {
+        let mut state = self.state.lock();
+        loop {
+            match Self::try_recv(&mut state) {
+                Err(Error::ChannelEmpty) => {}
+                res => return res,
+            }
+            if let Some(ref op) = op {
+                let remaining = op.remaining()?;
+                self.data_available
+                    .wait_for::<State<T>, M>(&mut state, remaining);
+            } else {
+                self.data_available.wait::<State<T>, M>(&mut state);
+            }
+        }
+    }
+    fn try_recv(state: &mut State<T>) -> Result<T> {
+        if let Some(value) = state.value.take() {
+            Ok(value)
+        } else if state.completed {
+            Err(Error::ChannelClosed)
+        } else {
+            Err(Error::ChannelEmpty)
+        }
+    }
+}
+
+/// Oneshot channel sender. Sends a single value, if dropped without sending, the receiver gets
+/// [`Error::ChannelClosed`]
+pub struct Sender<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    inner: Arc<OneshotInner<T, M, CV>>,
+}
+
+impl<T, M, CV> Sender<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Sends the value (non-blocking). Returns [`Error::ChannelClosed`] if the receiver has been
+    /// dropped
+    pub fn send(self, value: T) -> Result<()> {
+        self.inner
+            .complete(Some(value))
+            .map_err(|_| Error::ChannelClosed)
+    }
+    /// Returns true if the receiver is still alive
+    pub fn is_alive(&self) -> bool {
+        self.inner.state.lock().receiver_alive
+    }
+}
+
+impl<T, M, CV> Drop for Sender<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn drop(&mut self) {
+        let _ = self.inner.complete(None);
+    }
+}
+
+/// Oneshot channel receiver
+pub struct Receiver<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    inner: Arc<OneshotInner<T, M, CV>>,
+}
+
+impl<T, M, CV> Receiver<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Receives the value, blocks until it is sent or the sender is dropped
+    #[inline]
+    pub fn recv(&self) -> Result<T> {
+        self.inner.recv(None)
+    }
+    /// Receives the value with the given timeout
+    #[inline]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        self.inner.recv(Some(Operation::new(timeout)))
+    }
+    /// Tries to receive the value (non-blocking)
+    pub fn try_recv(&self) -> Result<T> {
+        OneshotInner::<T, M, CV>::try_recv(&mut self.inner.state.lock())
+    }
+    /// Receives the value asynchronously
+    pub fn recv_async(&self) -> impl Future<Output = Result<T>> + '_ {
+        Recv { inner: &self.inner }
+    }
+}
+
+impl<T, M, CV> DataReceiver<T> for Receiver<T, M, CV>
+where
+    M: RawMutexTrait + Sync,
+    CV: RawCondvar + RawCondvar<RawMutex = M> + Sync,
+{
+    fn recv(&self) -> Result<T> {
+        self.recv()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        self.recv_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<T> {
+        self.try_recv()
+    }
+    fn recv_async<'a>(&'a self) -> BoxFuture<'a, Result<T>>
+    where
+        T: Send + 'a,
+    {
+        Box::pin(self.recv_async())
+    }
+    fn is_alive(&self) -> bool {
+        let state = self.inner.state.lock();
+        !state.completed || state.value.is_some()
+    }
+}
+
+impl<T, M, CV> Drop for Receiver<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn drop(&mut self) {
+        self.inner.state.lock().receiver_alive = false;
+    }
+}
+
+struct Recv<'a, T, M, CV> {
+    inner: &'a OneshotInner<T, M, CV>,
+}
+
+impl<T, M, CV> Future for Recv<'_, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    type Output = Result<T>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.inner.state.lock();
+        match OneshotInner::<T, M, CV>::try_recv(&mut state) {
+            Err(Error::ChannelEmpty) => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            res => Poll::Ready(res),
+        }
+    }
+}
+
+/// Oneshot channel structure. Used to be destructurized into a sender and a receiver. A
+/// workaround to let the user use the default Mutex and Condvar types if others are not required
+pub struct Oneshot<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Channel sender
+    pub tx: Sender<T, M, CV>,
+    /// Channel receiver
+    pub rx: Receiver<T, M, CV>,
+}
+
+impl<T, M, CV> Oneshot<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Creates a new oneshot channel
+    pub fn new() -> Self {
+        let inner = Arc::new(OneshotInner {
+            state: lock_api::Mutex::new(State {
+                value: None,
+                completed: false,
+                receiver_alive: true,
+                waker: None,
+            }),
+            data_available: CV::new(),
+        });
+        Self {
+            tx: Sender {
+                inner: inner.clone(),
+            },
+            rx: Receiver { inner },
+        }
+    }
+}
+
+impl<T, M, CV> Default for Oneshot<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a oneshot channel and returns it as a tuple of a sender and a receiver
+pub fn channel<T, M, CV>() -> (Sender<T, M, CV>, Receiver<T, M, CV>)
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    let Oneshot { tx, rx } = Oneshot::new();
+    (tx, rx)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use super::Oneshot;
+    use crate::{DataReceiver, Error};
+
+    #[test]
+    fn test_oneshot() {
+        let Oneshot { tx, rx }: Oneshot<u32> = Oneshot::new();
+        assert!(matches!(rx.try_recv(), Err(Error::ChannelEmpty)));
+        assert!(matches!(
+            rx.recv_timeout(Duration::from_millis(10)),
+            Err(Error::Timeout)
+        ));
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            tx.send(42).unwrap();
+        });
+        assert_eq!(rx.recv().unwrap(), 42);
+        assert!(matches!(rx.recv(), Err(Error::ChannelClosed)));
+    }
+
+    #[test]
+    fn test_oneshot_dropped() {
+        let Oneshot { tx, rx }: Oneshot<u32> = Oneshot::new();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            drop(tx);
+        });
+        assert!(matches!(rx.recv(), Err(Error::ChannelClosed)));
+        let Oneshot { tx, rx }: Oneshot<u32> = Oneshot::new();
+        drop(rx);
+        assert!(matches!(tx.send(1), Err(Error::ChannelClosed)));
+        let Oneshot { tx, rx }: Oneshot<u32> = Oneshot::new();
+        assert!(DataReceiver::is_alive(&rx));
+        tx.send(1).unwrap();
+        // the sent value is pending
+        assert!(DataReceiver::is_alive(&rx));
+        assert_eq!(rx.recv().unwrap(), 1);
+        assert!(!DataReceiver::is_alive(&rx));
+    }
+
+    #[tokio::test]
+    async fn test_oneshot_async() {
+        let Oneshot { tx, rx }: Oneshot<u32> = Oneshot::new();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tx.send(42).unwrap();
+        });
+        assert_eq!(rx.recv_async().await.unwrap(), 42);
+    }
+}
@@ -118,6 +118,22 @@ where
             }
         }
     }
+    /// Returns a reference to the first available value, ignores expired ones
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.data.iter().find(|v| !v.is_expired())
+    }
+    /// Retains only the values specified by the predicate, expired values are removed as well.
+    /// Returns the number of removed values
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.data.len();
+        self.data.retain(|v| !v.is_expired() && f(v));
+        len - self.data.len()
+    }
     /// Clears the deque
     #[inline]
     pub fn clear(&mut self) {
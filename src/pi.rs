@@ -9,6 +9,10 @@ use lock_api::{GuardSend, RawMutex as RawMutexTrait, RawMutexTimed};
 
 use crate::condvar_api::{RawCondvar, WaitTimeoutResult};
 
+/// Lock-order and self-deadlock detection (active in debug builds with `deadlock_detection`
+/// feature only)
+pub mod lock_order;
+
 thread_local! {
     #[allow(clippy::cast_possible_truncation)]
     static TID: libc::pid_t = unsafe { libc::syscall(libc::SYS_gettid) as i32 }
@@ -175,6 +179,11 @@ pub struct PiLock {
 }
 
 impl PiLock {
+    #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+    #[inline]
+    fn id(&self) -> usize {
+        std::ptr::from_ref(self) as usize
+    }
     fn perform_lock(&self) {
         if self.blocked.load(Ordering::SeqCst) {
             // spin forever
@@ -182,6 +191,8 @@ impl PiLock {
                 thread::park();
             }
         }
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        lock_order::before_lock(self.id());
         let tid = tid();
         #[allow(clippy::cast_sign_loss)]
         let locked =
@@ -194,6 +205,8 @@ impl PiLock {
                 thread::yield_now();
             }
         }
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        lock_order::locked(self.id());
     }
     fn perform_try_lock(&self) -> bool {
         if self.blocked.load(Ordering::SeqCst) {
@@ -206,13 +219,16 @@ impl PiLock {
                 .value
                 .compare_exchange(0, tid as u32, Ordering::SeqCst, Ordering::SeqCst);
 
-        if locked.is_ok() {
-            true
-        } else {
-            self.futex.trylock_pi().is_ok()
+        let result = locked.is_ok() || self.futex.trylock_pi().is_ok();
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        if result {
+            lock_order::locked(self.id());
         }
+        result
     }
     fn perform_unlock(&self) {
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        lock_order::unlocked(self.id());
         let tid = tid();
         #[allow(clippy::cast_sign_loss)]
         let fast_unlocked =
@@ -231,6 +247,15 @@ impl PiLock {
     #[inline]
     fn block_forever(&self) {
         self.blocked.store(true, Ordering::SeqCst);
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        lock_order::forget(self.id());
+    }
+}
+
+#[cfg(all(feature = "deadlock_detection", debug_assertions))]
+impl Drop for PiLock {
+    fn drop(&mut self) {
+        lock_order::forget(self.id());
     }
 }
 
@@ -270,6 +295,8 @@ unsafe impl RawMutexTimed for PiLock {
     }
 
     fn try_lock_until(&self, ts: Self::Instant) -> bool {
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        lock_order::before_lock(self.id());
         let tid = tid();
         #[allow(clippy::cast_sign_loss)]
         let locked =
@@ -277,17 +304,19 @@ unsafe impl RawMutexTimed for PiLock {
                 .value
                 .compare_exchange(0, tid as u32, Ordering::SeqCst, Ordering::SeqCst);
 
-        if locked.is_ok() {
-            return true;
-        }
-
-        loop {
-            match self.futex.lock_pi_until(ts) {
-                Ok(()) => return true,
-                Err(linux_futex::TimedLockError::TryAgain) => (),
-                Err(linux_futex::TimedLockError::TimedOut) => return false,
-            }
+        let result = locked.is_ok()
+            || loop {
+                match self.futex.lock_pi_until(ts) {
+                    Ok(()) => break true,
+                    Err(linux_futex::TimedLockError::TryAgain) => (),
+                    Err(linux_futex::TimedLockError::TimedOut) => break false,
+                }
+            };
+        #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+        if result {
+            lock_order::locked(self.id());
         }
+        result
     }
 }
 
@@ -530,4 +559,41 @@ mod tests {
             }
         }
     }
+
+    #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "lock-order inversion")]
+    fn test_lock_order_inversion() {
+        let a = Mutex::new(0);
+        let b = Mutex::new(0);
+        {
+            let _a = a.lock();
+            let _b = b.lock();
+        }
+        let _b = b.lock();
+        let _a = a.lock();
+    }
+
+    #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+    #[test]
+    #[should_panic(expected = "self-deadlock")]
+    fn test_lock_order_self_deadlock() {
+        let a = Mutex::new(0);
+        let _a = a.lock();
+        let _a2 = a.lock();
+    }
+
+    #[cfg(all(feature = "deadlock_detection", debug_assertions))]
+    #[test]
+    fn test_lock_order_block_forever_exempt() {
+        let a = Mutex::new(0);
+        let b = Mutex::new(0);
+        {
+            let _b = b.lock();
+            super::block_forever(a.lock());
+        }
+        // a is blocked and no longer tracked, so b may be locked after it in any order
+        let _b = b.lock();
+        assert!(a.try_lock().is_none());
+    }
 }
@@ -0,0 +1,161 @@
+//! Lock-order tracking for [`crate::pi::PiLock`].
+//!
+//! Every thread keeps a list of the locks it currently holds. When a lock is acquired while
+//! other locks are held, an edge "held -> acquired" is recorded in a global lock graph. If the
+//! acquired lock can already reach a held one, the lock order is inverted and two threads may
+//! deadlock. Acquiring a lock which is already held by the current thread is reported as a
+//! self-deadlock.
+//!
+//! Locks, blocked with [`crate::pi::block_forever`], are exempt from tracking.
+//!
+//! Locks are identified by their addresses, so a mutex should not be moved in memory after it
+//! has been used (which is always true for mutexes shared with [`std::sync::Arc`]).
+//!
+//! The tracking is active in debug builds with `deadlock_detection` feature only. Otherwise the
+//! module is still available but the settings have no effect.
+#![cfg_attr(
+    not(all(feature = "deadlock_detection", debug_assertions)),
+    allow(dead_code)
+)]
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use tracing::error;
+
+/// Action, performed when a lock-order violation is detected
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum OnViolation {
+    #[default]
+    /// Panic the thread which has caused the violation (default)
+    Panic,
+    /// Log the violation with [`tracing::error!`] and continue
+    Log,
+}
+
+static ON_VIOLATION: AtomicU8 = AtomicU8::new(0);
+
+// lock -> locks acquired while holding it
+static GRAPH: Mutex<BTreeMap<usize, BTreeSet<usize>>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Sets the action, performed when a lock-order violation is detected
+pub fn set_on_violation(action: OnViolation) {
+    ON_VIOLATION.store(
+        match action {
+            OnViolation::Panic => 0,
+            OnViolation::Log => 1,
+        },
+        Ordering::SeqCst,
+    );
+}
+
+/// Returns the action, performed when a lock-order violation is detected
+pub fn on_violation() -> OnViolation {
+    match ON_VIOLATION.load(Ordering::SeqCst) {
+        0 => OnViolation::Panic,
+        _ => OnViolation::Log,
+    }
+}
+
+fn report(message: String) {
+    match on_violation() {
+        OnViolation::Panic => panic!("{}", message),
+        OnViolation::Log => error!("{}", message),
+    }
+}
+
+fn thread_name() -> String {
+    thread::current().name().unwrap_or("<unnamed>").to_owned()
+}
+
+fn reaches(graph: &BTreeMap<usize, BTreeSet<usize>>, from: usize, to: usize) -> bool {
+    let mut stack = vec![from];
+    let mut visited = BTreeSet::new();
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
+        }
+        if !visited.insert(node) {
+            continue;
+        }
+        if let Some(next) = graph.get(&node) {
+            stack.extend(next.iter().copied());
+        }
+    }
+    false
+}
+
+/// Called before a blocking lock attempt
+pub(super) fn before_lock(lock: usize) {
+    let held = HELD.with(|h| h.borrow().clone());
+    if held.contains(&lock) {
+        report(format!(
+            "self-deadlock: lock {:#x} is already held by thread {}",
+            lock,
+            thread_name()
+        ));
+        return;
+    }
+    let mut inverted = None;
+    {
+        let mut graph = GRAPH
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        for h in held {
+            if graph.get(&h).is_some_and(|next| next.contains(&lock)) {
+                continue;
+            }
+            if inverted.is_none() && reaches(&graph, lock, h) {
+                inverted = Some(h);
+                continue;
+            }
+            graph.entry(h).or_default().insert(lock);
+        }
+    }
+    if let Some(h) = inverted {
+        report(format!(
+            "lock-order inversion: thread {} acquires lock {:#x} while holding {:#x}, \
+             the opposite order has been observed before",
+            thread_name(),
+            lock,
+            h
+        ));
+    }
+}
+
+/// Called after a lock has been acquired
+pub(super) fn locked(lock: usize) {
+    HELD.with(|h| h.borrow_mut().push(lock));
+}
+
+/// Called after a lock has been released
+pub(super) fn unlocked(lock: usize) {
+    HELD.with(|h| {
+        let mut held = h.borrow_mut();
+        if let Some(pos) = held.iter().rposition(|l| *l == lock) {
+            held.remove(pos);
+        }
+    });
+}
+
+/// Removes a lock from tracking (the lock is blocked forever or destroyed)
+pub(super) fn forget(lock: usize) {
+    unlocked(lock);
+    let mut graph = GRAPH
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    graph.remove(&lock);
+    for next in graph.values_mut() {
+        next.remove(&lock);
+    }
+}
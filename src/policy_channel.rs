@@ -3,7 +3,7 @@ use crate::{
     condvar_api::RawCondvar,
     data_policy::{DataDeliveryPolicy, StorageTryPushOutput},
     locking::{Condvar, RawMutex},
-    pdeque,
+    pdeque, Result,
 };
 
 impl<T> ChannelStorage<T> for pdeque::Deque<T>
@@ -36,6 +36,23 @@ where
     fn is_empty(&self) -> bool {
         Self::is_empty(self)
     }
+
+    fn peek(&self) -> Result<Option<&T>> {
+        Ok(Self::peek(self))
+    }
+
+    fn retain<F>(&mut self, f: F) -> Result<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        Ok(Self::retain(self, f))
+    }
+
+    fn clear(&mut self) -> Result<usize> {
+        let len = Self::len(self);
+        Self::clear(self);
+        Ok(len)
+    }
 }
 
 /// Channel sender
@@ -279,4 +296,52 @@ mod test {
         assert!(tx.is_empty());
         assert!(rx.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_async_view() {
+        let super::Bounded { tx, rx }: super::Bounded<Message> = super::Bounded::new(2);
+        // a real-time thread sends, an async task receives
+        let handle = thread::spawn(move || {
+            for i in 0..10 {
+                tx.send(Message::Test(i)).unwrap();
+            }
+        });
+        let mut received = 0;
+        while let Ok(msg) = rx.recv_async().await {
+            assert!(matches!(msg, Message::Test(i) if i == received));
+            received += 1;
+        }
+        assert_eq!(received, 10);
+        handle.join().unwrap();
+        // an async task sends, a real-time thread receives
+        let super::Bounded { tx, rx }: super::Bounded<Message> = super::Bounded::new(1);
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            rx.into_iter().count()
+        });
+        for i in 0..10 {
+            tx.send_async(Message::Test(i)).await.unwrap();
+        }
+        drop(tx);
+        assert_eq!(handle.join().unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_async_view_cancel() {
+        let super::Bounded { tx, rx }: super::Bounded<Message> = super::Bounded::new(1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), rx.recv_async())
+                .await
+                .is_err()
+        );
+        tx.send_async(Message::Test(1)).await.unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), tx.send_async(Message::Test(2)))
+                .await
+                .is_err()
+        );
+        assert!(matches!(rx.recv_async().await, Ok(Message::Test(1))));
+        drop(tx);
+        assert!(matches!(rx.recv_async().await, Err(Error::ChannelClosed)));
+    }
 }
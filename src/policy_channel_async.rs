@@ -1,14 +1,79 @@
 use crate::{
     base_channel_async::{make_channel, BaseChannelAsync, BaseReceiverAsync, BaseSenderAsync},
+    condvar_api::RawCondvar,
     data_policy::DataDeliveryPolicy,
+    locking::{Condvar, RawMutex},
     pdeque,
 };
+use lock_api::RawMutex as RawMutexTrait;
 
 /// Channel sender
-pub type Sender<T> = BaseSenderAsync<T, pdeque::Deque<T>>;
+pub type Sender<T, M = RawMutex, CV = Condvar> = BaseSenderAsync<T, pdeque::Deque<T>, M, CV>;
 
 /// Channel receiver
-pub type Receiver<T> = BaseReceiverAsync<T, pdeque::Deque<T>>;
+pub type Receiver<T, M = RawMutex, CV = Condvar> = BaseReceiverAsync<T, pdeque::Deque<T>, M, CV>;
+
+/// Bounded async policy channel structure. Used to be destructurized into a sender and a receiver.
+/// Allows to specify custom Mutex and Condvar types
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+pub struct Bounded<T, M = RawMutex, CV = Condvar>
+where
+    T: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Channel sender
+    pub tx: Sender<T, M, CV>,
+    /// Channel receiver
+    pub rx: Receiver<T, M, CV>,
+}
+
+impl<T, M, CV> Bounded<T, M, CV>
+where
+    T: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Creates a new bounded async policy channel with no message priority ordering
+    pub fn new(capacity: usize) -> Self {
+        let (tx, rx) = make_channel(BaseChannelAsync::new(capacity, false));
+        Self { tx, rx }
+    }
+}
+
+/// Bounded async policy channel structure with message priority ordering turned on. Used to be
+/// destructurized into a sender and a receiver
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+pub struct Ordered<T, M = RawMutex, CV = Condvar>
+where
+    T: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Channel sender
+    pub tx: Sender<T, M, CV>,
+    /// Channel receiver
+    pub rx: Receiver<T, M, CV>,
+}
+
+impl<T, M, CV> Ordered<T, M, CV>
+where
+    T: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar<RawMutex = M>,
+{
+    /// Creates a new bounded async policy channel with message priority ordering
+    pub fn new(capacity: usize) -> Self {
+        let (tx, rx) = make_channel(BaseChannelAsync::new(capacity, true));
+        Self { tx, rx }
+    }
+}
 
 /// Creates a bounded async channel which respects [`DataDeliveryPolicy`] rules with no message
 /// priority ordering
@@ -17,8 +82,8 @@ pub type Receiver<T> = BaseReceiverAsync<T, pdeque::Deque<T>>;
 ///
 /// Will panic if the capacity is zero
 pub fn bounded<T: DataDeliveryPolicy>(capacity: usize) -> (Sender<T>, Receiver<T>) {
-    let ch = BaseChannelAsync::new(capacity, false);
-    make_channel(ch)
+    let Bounded { tx, rx } = Bounded::new(capacity);
+    (tx, rx)
 }
 
 /// Creates a bounded async channel which respects [`DataDeliveryPolicy`] rules and has got message
@@ -28,8 +93,8 @@ pub fn bounded<T: DataDeliveryPolicy>(capacity: usize) -> (Sender<T>, Receiver<T
 ///
 /// Will panic if the capacity is zero
 pub fn ordered<T: DataDeliveryPolicy>(capacity: usize) -> (Sender<T>, Receiver<T>) {
-    let ch = BaseChannelAsync::new(capacity, true);
-    make_channel(ch)
+    let Ordered { tx, rx } = Ordered::new(capacity);
+    (tx, rx)
 }
 
 #[cfg(test)]
@@ -0,0 +1,711 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{
+    base_channel::{BoxFuture, DataReceiver, DataSender},
+    condvar_api::RawCondvar,
+    locking::{Condvar, RawMutex},
+    ops::Operation,
+    Error, Result,
+};
+use lock_api::RawMutex as RawMutexTrait;
+
+struct State<T> {
+    // a value, offered by a sender
+    slot: Option<T>,
+    // id of the value in the slot
+    slot_id: u64,
+    // id of the last value taken by a receiver
+    taken_id: u64,
+    next_id: u64,
+    senders: usize,
+    receivers: usize,
+    // receivers, blocked or parked waiting for a value
+    waiting_receivers: usize,
+    send_wakers: Vec<Waker>,
+    recv_wakers: Vec<Waker>,
+}
+
+impl<T> State<T> {
+    fn offer(&mut self, value: T) -> u64 {
+        self.next_id += 1;
+        self.slot = Some(value);
+        self.slot_id = self.next_id;
+        self.slot_id
+    }
+    fn take(&mut self) -> Option<T> {
+        let value = self.slot.take()?;
+        self.taken_id = self.slot_id;
+        Some(value)
+    }
+    // takes the value back if it has not been taken by a receiver
+    fn withdraw(&mut self, id: u64) -> Option<T> {
+        if self.slot_id == id {
+            self.slot.take()
+        } else {
+            None
+        }
+    }
+    fn is_taken(&self, id: u64) -> bool {
+        self.taken_id >= id
+    }
+    fn append_waker(wakers: &mut Vec<Waker>, waker: &Waker) {
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+struct RendezvousInner<T, M, CV> {
+    state: lock_api::Mutex<M, State<T>>,
+    // notifies receivers that a value has been offered or all senders are gone
+    data_available: CV,
+    // notifies senders that a value has been taken/withdrawn or all receivers are gone
+    space_available: CV,
+}
+
+impl<T, M, CV> RendezvousInner<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn notify_receivers(&self, state: &mut State<T>) {
+        self.data_available.notify_all();
+        for waker in state.recv_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+    fn notify_senders(&self, state: &mut State<T>) {
+        self.space_available.notify_all();
+        for waker in state.send_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+    fn send(&self, value: T, op: Option<Operation>) -> Result<()> {
+        let mut state = self.state.lock();
+        macro_rules! wait {
+            () => {
+                if let Some(ref op) = op {
+                    let remaining = op.remaining()?;
+                    self.space_available
+                        .wait_for::<State<T>, M>(&mut state, remaining);
+                } else {
+                    self.space_available.wait::<State<T>, M>(&mut state);
+                }
+            };
+        }
+        loop {
+            if state.receivers == 0 {
+                return Err(Error::ChannelClosed);
+            }
+            if state.slot.is_none() {
+                break;
+            }
+            wait!();
+        }
+        let id = state.offer(value);
+        self.notify_receivers(&mut state);
+        loop {
+            if state.is_taken(id) {
+                return Ok(());
+            }
+            let withdraw_reason = if state.receivers == 0 {
+                Some(Error::ChannelClosed)
+            } else if let Some(Err(e)) = op.as_ref().map(Operation::remaining) {
+                Some(e)
+            } else {
+                None
+            };
+            if let Some(e) = withdraw_reason {
+                state.withdraw(id);
+                self.notify_senders(&mut state);
+                return Err(e);
+            }
+            if let Some(ref op) = op {
+                // the remaining time is checked above
+                let remaining = op.remaining().unwrap_or_default();
+                self.space_available
+                    .wait_for::<State<T>, M>(&mut state, remaining);
+            } else {
+                self.space_available.wait::<State<T>, M>(&mut state);
+            }
+        }
+    }
+    // hands the value over only if a receiver is waiting for it
+    fn try_send(&self, value: T) -> Result<()> {
+        let mut state = self.state.lock();
+        if state.receivers == 0 {
+            return Err(Error::ChannelClosed);
+        }
+        if state.slot.is_some() || state.waiting_receivers == 0 {
+            return Err(Error::ChannelFull);
+        }
+        state.offer(value);
+        self.notify_receivers(&mut state);
+        Ok(())
+    }
+    fn try_recv(&self, state: &mut State<T>) -> Result<T> {
+        if let Some(value) = state.take() {
+            self.notify_senders(state);
+            Ok(value)
+        } else if state.senders == 0 {
+            Err(Error::ChannelClosed)
+        } else {
+            Err(Error::ChannelEmpty)
+        }
+    }
+    fn recv(&self, op: Option<Operation>) -> Result<T> {
+        let mut state = self.state.lock();
+        loop {
+            match self.try_recv(&mut state) {
+                Err(Error::ChannelEmpty) => {}
+                res => return res,
+            }
+            let remaining = op.as_ref().map(Operation::remaining).transpose()?;
+            state.waiting_receivers += 1;
+            if let Some(remaining) = remaining {
+                self.data_available
+                    .wait_for::<State<T>, M>(&mut state, remaining);
+            } else {
+                self.data_available.wait::<State<T>, M>(&mut state);
+            }
+            state.waiting_receivers -= 1;
+        }
+    }
+}
+
+/// Rendezvous (zero-capacity) channel sender. A value is handed over directly to a receiver,
+/// sending is completed only when a receiver has taken the value
+pub struct Sender<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    inner: Arc<RendezvousInner<T, M, CV>>,
+}
+
+impl<T, M, CV> Sender<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Sends a value and blocks until a receiver takes it
+    #[inline]
+    pub fn send(&self, value: T) -> Result<()> {
+        self.inner.send(value, None)
+    }
+    /// Sends a value and blocks until a receiver takes it or the timeout is reached. If the
+    /// method returns an error, the value is guaranteed to be not delivered
+    #[inline]
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<()> {
+        self.inner.send(value, Some(Operation::new(timeout)))
+    }
+    /// Hands the value over to a receiver, which is currently waiting for a value (non-blocking).
+    /// Returns [`Error::ChannelFull`] if there are no waiting receivers. If the waiting receiver
+    /// is cancelled (e.g. its future is dropped), the value is taken by the next receiver
+    #[inline]
+    pub fn try_send(&self, value: T) -> Result<()> {
+        self.inner.try_send(value)
+    }
+    /// Sends a value asynchronously, the future is completed when a receiver takes the value.
+    /// If the future is dropped before completion, the value is guaranteed to be not delivered
+    #[inline]
+    pub fn send_async(&self, value: T) -> impl Future<Output = Result<()>> + '_ {
+        Send {
+            inner: &*self.inner,
+            value: Some(value),
+            id: None,
+        }
+    }
+    /// Returns true if the channel is alive
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.inner.state.lock().receivers > 0
+    }
+}
+
+impl<T, M, CV> DataSender<T> for Sender<T, M, CV>
+where
+    M: RawMutexTrait + Sync,
+    CV: RawCondvar + RawCondvar<RawMutex = M> + Sync,
+{
+    fn send(&self, value: T) -> Result<()> {
+        self.send(value)
+    }
+    fn send_timeout(&self, value: T, timeout: Duration) -> Result<()> {
+        self.send_timeout(value, timeout)
+    }
+    fn try_send(&self, value: T) -> Result<()> {
+        self.try_send(value)
+    }
+    fn send_async<'a>(&'a self, value: T) -> BoxFuture<'a, Result<()>>
+    where
+        T: std::marker::Send + 'a,
+    {
+        Box::pin(self.send_async(value))
+    }
+    fn is_alive(&self) -> bool {
+        self.is_alive()
+    }
+}
+
+impl<T, M, CV> Clone for Sender<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    fn clone(&self) -> Self {
+        self.inner.state.lock().senders += 1;
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T, M, CV> Drop for Sender<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.inner.data_available.notify_all();
+            for waker in state.recv_wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Rendezvous (zero-capacity) channel receiver
+pub struct Receiver<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    inner: Arc<RendezvousInner<T, M, CV>>,
+}
+
+impl<T, M, CV> Receiver<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Receives a value, blocks until a sender offers one
+    #[inline]
+    pub fn recv(&self) -> Result<T> {
+        self.inner.recv(None)
+    }
+    /// Receives a value, blocks until a sender offers one or the timeout is reached
+    #[inline]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        self.inner.recv(Some(Operation::new(timeout)))
+    }
+    /// Takes a value if a sender is currently offering one (non-blocking)
+    #[inline]
+    pub fn try_recv(&self) -> Result<T> {
+        self.inner.try_recv(&mut self.inner.state.lock())
+    }
+    /// Receives a value asynchronously
+    #[inline]
+    pub fn recv_async(&self) -> impl Future<Output = Result<T>> + '_ {
+        Recv {
+            inner: &*self.inner,
+            waiting: false,
+        }
+    }
+    /// Returns true if the channel is alive
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.inner.state.lock().senders > 0
+    }
+}
+
+impl<T, M, CV> Iterator for Receiver<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv().ok()
+    }
+}
+
+impl<T, M, CV> DataReceiver<T> for Receiver<T, M, CV>
+where
+    M: RawMutexTrait + Sync,
+    CV: RawCondvar + RawCondvar<RawMutex = M> + Sync,
+{
+    fn recv(&self) -> Result<T> {
+        self.recv()
+    }
+    fn recv_timeout(&self, timeout: Duration) -> Result<T> {
+        self.recv_timeout(timeout)
+    }
+    fn try_recv(&self) -> Result<T> {
+        self.try_recv()
+    }
+    fn recv_async<'a>(&'a self) -> BoxFuture<'a, Result<T>>
+    where
+        T: std::marker::Send + 'a,
+    {
+        Box::pin(self.recv_async())
+    }
+    fn is_alive(&self) -> bool {
+        self.is_alive()
+    }
+}
+
+impl<T, M, CV> Clone for Receiver<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    fn clone(&self) -> Self {
+        self.inner.state.lock().receivers += 1;
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T, M, CV> Drop for Receiver<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock();
+        state.receivers -= 1;
+        if state.receivers == 0 {
+            self.inner.space_available.notify_all();
+            for waker in state.send_wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+struct Send<'a, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    inner: &'a RendezvousInner<T, M, CV>,
+    value: Option<T>,
+    // set when the value has been offered
+    id: Option<u64>,
+}
+
+impl<T, M, CV> Unpin for Send<'_, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+}
+
+impl<T, M, CV> Future for Send<'_, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    type Output = Result<()>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner;
+        let mut state = inner.state.lock();
+        if let Some(id) = self.id {
+            if state.is_taken(id) {
+                self.id = None;
+                return Poll::Ready(Ok(()));
+            }
+            if state.receivers == 0 {
+                state.withdraw(id);
+                self.id = None;
+                inner.notify_senders(&mut state);
+                return Poll::Ready(Err(Error::ChannelClosed));
+            }
+        } else {
+            if state.receivers == 0 {
+                return Poll::Ready(Err(Error::ChannelClosed));
+            }
+            if state.slot.is_none() {
+                let value = self.value.take().expect("value already sent");
+                self.id = Some(state.offer(value));
+                inner.notify_receivers(&mut state);
+            }
+        }
+        State::<T>::append_waker(&mut state.send_wakers, cx.waker());
+        Poll::Pending
+    }
+}
+
+impl<T, M, CV> Drop for Send<'_, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            let mut state = self.inner.state.lock();
+            if state.withdraw(id).is_some() {
+                self.inner.notify_senders(&mut state);
+            }
+        }
+    }
+}
+
+struct Recv<'a, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    inner: &'a RendezvousInner<T, M, CV>,
+    // the future is counted as a waiting receiver
+    waiting: bool,
+}
+
+impl<T, M, CV> Future for Recv<'_, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    type Output = Result<T>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner;
+        let mut state = inner.state.lock();
+        match inner.try_recv(&mut state) {
+            Err(Error::ChannelEmpty) => {
+                if !self.waiting {
+                    state.waiting_receivers += 1;
+                    self.waiting = true;
+                }
+                State::<T>::append_waker(&mut state.recv_wakers, cx.waker());
+                Poll::Pending
+            }
+            res => {
+                if self.waiting {
+                    state.waiting_receivers -= 1;
+                    self.waiting = false;
+                }
+                Poll::Ready(res)
+            }
+        }
+    }
+}
+
+impl<T, M, CV> Drop for Recv<'_, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn drop(&mut self) {
+        if self.waiting {
+            self.inner.state.lock().waiting_receivers -= 1;
+        }
+    }
+}
+
+/// Rendezvous channel structure. Used to be destructurized into a sender and a receiver. A
+/// workaround to let the user use the default Mutex and Condvar types if others are not required
+pub struct Rendezvous<T, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    /// Channel sender
+    pub tx: Sender<T, M, CV>,
+    /// Channel receiver
+    pub rx: Receiver<T, M, CV>,
+}
+
+impl<T, M, CV> Rendezvous<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    /// Creates a new rendezvous channel
+    pub fn new() -> Self {
+        let inner = Arc::new(RendezvousInner {
+            state: lock_api::Mutex::new(State {
+                slot: None,
+                slot_id: 0,
+                taken_id: 0,
+                next_id: 0,
+                senders: 1,
+                receivers: 1,
+                waiting_receivers: 0,
+                send_wakers: Vec::new(),
+                recv_wakers: Vec::new(),
+            }),
+            data_available: CV::new(),
+            space_available: CV::new(),
+        });
+        Self {
+            tx: Sender {
+                inner: inner.clone(),
+            },
+            rx: Receiver { inner },
+        }
+    }
+}
+
+impl<T, M, CV> Default for Rendezvous<T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a rendezvous channel and returns it as a tuple of a sender and a receiver
+pub fn channel<T, M, CV>() -> (Sender<T, M, CV>, Receiver<T, M, CV>)
+where
+    M: RawMutexTrait,
+    CV: RawCondvar,
+{
+    let Rendezvous { tx, rx } = Rendezvous::new();
+    (tx, rx)
+}
+
+/// Creates a new rendezvous channel and automatically destructurizes it into a sender and a
+/// receiver
+#[macro_export]
+macro_rules! channel_rendezvous {
+    () => {{
+        let $crate::rendezvous::Rendezvous { tx, rx } = $crate::rendezvous::Rendezvous::<_>::new();
+        (tx, rx)
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        sync::Arc,
+        thread,
+        time::Duration,
+    };
+
+    use crate::Error;
+
+    #[test]
+    fn test_handoff() {
+        let (tx, rx) = crate::channel_rendezvous!();
+        let received = Arc::new(AtomicBool::new(false));
+        let r = received.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            r.store(true, Ordering::SeqCst);
+            rx.recv().unwrap()
+        });
+        tx.send(42).unwrap();
+        // send returns only after the value has been taken
+        assert!(received.load(Ordering::SeqCst));
+        assert_eq!(handle.join().unwrap(), 42);
+        // the receiver is dropped
+        assert!(matches!(tx.send(1), Err(Error::ChannelClosed)));
+    }
+
+    #[test]
+    fn test_try_send() {
+        let (tx, rx) = crate::channel_rendezvous!();
+        // no receiver is waiting
+        assert!(matches!(tx.try_send(1), Err(Error::ChannelFull)));
+        let handle = thread::spawn(move || rx.recv());
+        let tx: Box<dyn crate::DataSender<u32>> = Box::new(tx);
+        let mut value = 2;
+        loop {
+            match tx.try_send(value) {
+                Ok(()) => break,
+                Err(Error::ChannelFull) => thread::sleep(Duration::from_millis(1)),
+                Err(e) => panic!("{}", e),
+            }
+            value += 1;
+        }
+        assert_eq!(handle.join().unwrap().unwrap(), value);
+        assert!(matches!(tx.try_send(1), Err(Error::ChannelClosed)));
+    }
+
+    #[test]
+    fn test_send_timeout() {
+        let (tx, rx) = crate::channel_rendezvous!();
+        assert!(matches!(
+            tx.send_timeout(1, Duration::from_millis(10)),
+            Err(Error::Timeout)
+        ));
+        // the value is withdrawn after the timeout
+        assert!(matches!(rx.try_recv(), Err(Error::ChannelEmpty)));
+        let handle = thread::spawn(move || rx.recv_timeout(Duration::from_secs(1)));
+        tx.send_timeout(2, Duration::from_secs(1)).unwrap();
+        assert_eq!(handle.join().unwrap().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_multiple_senders() {
+        let (tx, rx) = crate::channel_rendezvous!();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for j in 0..100 {
+                        tx.send(i * 100 + j).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(tx);
+        let mut received: Vec<_> = rx.collect();
+        received.sort_unstable();
+        assert_eq!(received, (0..400).collect::<Vec<_>>());
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async() {
+        let super::Rendezvous { tx, rx }: super::Rendezvous<u32> = super::Rendezvous::new();
+        let tx = Arc::new(tx);
+        let tx_c = tx.clone();
+        let handle = tokio::spawn(async move {
+            for i in 0..10 {
+                tx_c.send_async(i).await.unwrap();
+            }
+        });
+        for i in 0..10 {
+            assert_eq!(rx.recv_async().await.unwrap(), i);
+        }
+        handle.await.unwrap();
+        // a cancelled send must not deliver the value
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), tx.send_async(100))
+                .await
+                .is_err()
+        );
+        assert!(matches!(rx.try_recv(), Err(Error::ChannelEmpty)));
+        // a parked async receiver accepts try_send, a cancelled one does not
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), rx.recv_async())
+                .await
+                .is_err()
+        );
+        assert!(matches!(tx.try_send(1), Err(Error::ChannelFull)));
+        let rx = Arc::new(rx);
+        let rx_c = rx.clone();
+        let handle = tokio::spawn(async move { rx_c.recv_async().await });
+        while tx.try_send(2).is_err() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(handle.await.unwrap().unwrap(), 2);
+    }
+}
@@ -0,0 +1,338 @@
+use std::time::Duration;
+
+use crate::{
+    condvar_api::RawCondvar,
+    data_policy::{DataDeliveryPolicy, DeliveryPolicy},
+    locking::{Condvar, RawMutex},
+    oneshot,
+    ops::Operation,
+    policy_channel, Result,
+};
+use lock_api::RawMutex as RawMutexTrait;
+
+/// An RPC request, received by a server. Contains the payload and the reply handle. If the
+/// request is dropped without a reply, the caller gets [`crate::Error::ChannelClosed`]
+///
+/// The request respects the payload [`DataDeliveryPolicy`]. Requests, which callers have already
+/// given up on (e.g. timed out), are considered as expired and are not delivered.
+pub struct Request<Q, R, M = RawMutex, CV = Condvar>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    payload: Q,
+    reply: oneshot::Sender<R, M, CV>,
+}
+
+impl<Q, R, M, CV> Request<Q, R, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Request payload
+    #[inline]
+    pub fn payload(&self) -> &Q {
+        &self.payload
+    }
+    /// Sends the response to the caller (non-blocking). Returns
+    /// [`crate::Error::ChannelClosed`] if the caller has gone
+    #[inline]
+    pub fn reply(self, response: R) -> Result<()> {
+        self.reply.send(response)
+    }
+    /// Returns true if the caller has gone (e.g. the call has timed out)
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        !self.reply.is_alive()
+    }
+    /// Splits the request into the payload and the reply handle
+    #[inline]
+    pub fn into_parts(self) -> (Q, oneshot::Sender<R, M, CV>) {
+        (self.payload, self.reply)
+    }
+}
+
+impl<Q, R, M, CV> DataDeliveryPolicy for Request<Q, R, M, CV>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn delivery_policy(&self) -> DeliveryPolicy {
+        self.payload.delivery_policy()
+    }
+    fn priority(&self) -> usize {
+        self.payload.priority()
+    }
+    fn eq_kind(&self, other: &Self) -> bool {
+        self.payload.eq_kind(&other.payload)
+    }
+    fn is_expired(&self) -> bool {
+        self.payload.is_expired() || self.is_cancelled()
+    }
+}
+
+/// RPC server, a policy channel receiver of requests
+pub type Server<Q, R, M = RawMutex, CV = Condvar> =
+    policy_channel::Receiver<Request<Q, R, M, CV>, M, CV>;
+
+/// RPC client. Sends requests to the server and waits for the responses
+pub struct Client<Q, R, M = RawMutex, CV = Condvar>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    tx: policy_channel::Sender<Request<Q, R, M, CV>, M, CV>,
+}
+
+impl<Q, R, M, CV> Clone for Client<Q, R, M, CV>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<Q, R, M, CV> Client<Q, R, M, CV>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Calls the server and waits for the response (blocking)
+    pub fn call(&self, payload: Q) -> Result<R> {
+        let oneshot::Oneshot { tx, rx } = oneshot::Oneshot::new();
+        self.tx.send(Request { payload, reply: tx })?;
+        rx.recv()
+    }
+    /// Calls the server and waits for the response with the given timeout. The timeout covers
+    /// both queueing the request and waiting for the response
+    pub fn call_timeout(&self, payload: Q, timeout: Duration) -> Result<R> {
+        let op = Operation::new(timeout);
+        let oneshot::Oneshot { tx, rx } = oneshot::Oneshot::new();
+        self.tx
+            .send_timeout(Request { payload, reply: tx }, timeout)?;
+        rx.recv_timeout(op.remaining()?)
+    }
+    /// Returns true if the server is alive
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.tx.is_alive()
+    }
+}
+
+/// Bounded RPC channel structure. Used to be destructurized into a client and a server. A
+/// workaround to let the user use the default Mutex and Condvar types if others are not required
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+pub struct Bounded<Q, R, M = RawMutex, CV = Condvar>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// RPC client
+    pub client: Client<Q, R, M, CV>,
+    /// RPC server
+    pub server: Server<Q, R, M, CV>,
+}
+
+impl<Q, R, M, CV> Bounded<Q, R, M, CV>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Creates a new bounded RPC channel
+    pub fn new(capacity: usize) -> Self {
+        let (tx, server) = policy_channel::bounded(capacity);
+        Self {
+            client: Client { tx },
+            server,
+        }
+    }
+}
+
+/// Bounded RPC channel structure with request priority ordering turned on. Used to be
+/// destructurized into a client and a server
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+pub struct Ordered<Q, R, M = RawMutex, CV = Condvar>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// RPC client
+    pub client: Client<Q, R, M, CV>,
+    /// RPC server
+    pub server: Server<Q, R, M, CV>,
+}
+
+impl<Q, R, M, CV> Ordered<Q, R, M, CV>
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    /// Creates a new bounded and ordered RPC channel
+    pub fn new(capacity: usize) -> Self {
+        let (tx, server) = policy_channel::ordered(capacity);
+        Self {
+            client: Client { tx },
+            server,
+        }
+    }
+}
+
+/// Creates a bounded RPC channel with no request priority ordering
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+#[allow(clippy::type_complexity)]
+pub fn bounded<Q, R, M, CV>(capacity: usize) -> (Client<Q, R, M, CV>, Server<Q, R, M, CV>)
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    let Bounded { client, server } = Bounded::new(capacity);
+    (client, server)
+}
+
+/// Creates a bounded RPC channel with request priority ordering turned on
+///
+/// # Panics
+///
+/// Will panic if the capacity is zero
+#[allow(clippy::type_complexity)]
+pub fn ordered<Q, R, M, CV>(capacity: usize) -> (Client<Q, R, M, CV>, Server<Q, R, M, CV>)
+where
+    Q: DataDeliveryPolicy,
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    let Ordered { client, server } = Ordered::new(capacity);
+    (client, server)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use crate::{
+        data_policy::{DataDeliveryPolicy, DeliveryPolicy},
+        Error,
+    };
+
+    use super::{Bounded, Ordered};
+
+    #[derive(Debug)]
+    enum Cmd {
+        Add(u32, u32),
+        Ignore,
+        Urgent,
+        Status,
+    }
+
+    impl DataDeliveryPolicy for Cmd {
+        fn delivery_policy(&self) -> DeliveryPolicy {
+            match self {
+                Cmd::Status => DeliveryPolicy::Single,
+                _ => DeliveryPolicy::Always,
+            }
+        }
+        fn priority(&self) -> usize {
+            match self {
+                Cmd::Urgent => 0,
+                _ => 100,
+            }
+        }
+    }
+
+    #[test]
+    fn test_rpc_call() {
+        let Bounded { client, server }: Bounded<Cmd, u32> = Bounded::new(4);
+        let handle = thread::spawn(move || {
+            for req in server {
+                match *req.payload() {
+                    Cmd::Add(a, b) => req.reply(a + b).unwrap(),
+                    Cmd::Ignore => {}
+                    Cmd::Urgent | Cmd::Status => req.reply(0).unwrap(),
+                }
+            }
+        });
+        assert_eq!(client.call(Cmd::Add(2, 3)).unwrap(), 5);
+        assert!(matches!(
+            client.call(Cmd::Ignore),
+            Err(Error::ChannelClosed)
+        ));
+        assert_eq!(
+            client
+                .call_timeout(Cmd::Add(1, 1), Duration::from_secs(1))
+                .unwrap(),
+            2
+        );
+        drop(client);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_rpc_timeout() {
+        let Bounded { client, server }: Bounded<Cmd, u32> = Bounded::new(4);
+        assert!(matches!(
+            client.call_timeout(Cmd::Add(1, 2), Duration::from_millis(10)),
+            Err(Error::Timeout)
+        ));
+        // the caller has gone, the request is expired
+        assert!(matches!(server.try_recv(), Err(Error::ChannelEmpty)));
+        drop(server);
+        assert!(matches!(
+            client.call(Cmd::Add(1, 2)),
+            Err(Error::ChannelClosed)
+        ));
+    }
+
+    #[test]
+    fn test_rpc_policy() {
+        let Ordered { client, server }: Ordered<Cmd, u32> = Ordered::new(8);
+        let callers: Vec<_> = [Cmd::Add(1, 1), Cmd::Status, Cmd::Status, Cmd::Urgent]
+            .into_iter()
+            .map(|cmd| {
+                let client = client.clone();
+                let handle = thread::spawn(move || client.call(cmd));
+                thread::sleep(Duration::from_millis(10));
+                handle
+            })
+            .collect();
+        let mut received = Vec::new();
+        while let Ok(req) = server.try_recv() {
+            received.push(format!("{:?}", req.payload()));
+            req.reply(1).unwrap();
+        }
+        insta::assert_debug_snapshot!(received, @r###"
+        [
+            "Urgent",
+            "Add(1, 1)",
+            "Status",
+        ]
+        "###);
+        let results: Vec<_> = callers
+            .into_iter()
+            .map(|h| h.join().unwrap().is_ok())
+            .collect();
+        // the replaced status request is dropped, its caller gets an error
+        assert_eq!(results, [true, false, true, true]);
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use bma_ts::Monotonic;
+
+use crate::{
+    locking::{Condvar, RawMutex},
+    oneshot, thread_rt,
+    time::{self, IntervalStats, SleepMode},
+    Error, Result,
+};
+
+type TaskFn = Box<dyn FnMut() + Send + 'static>;
+
+/// A periodic task
+pub struct Task {
+    name: String,
+    period: Duration,
+    phase: Duration,
+    deadline: Option<Duration>,
+    params: thread_rt::Params,
+    f: TaskFn,
+}
+
+impl Task {
+    /// Creates a new periodic task. The function is called once per period
+    pub fn new<F>(name: &str, period: Duration, f: F) -> Self
+    where
+        F: FnMut() + Send + 'static,
+    {
+        Self {
+            name: name.to_owned(),
+            period,
+            phase: Duration::ZERO,
+            deadline: None,
+            params: thread_rt::Params::default(),
+            f: Box::new(f),
+        }
+    }
+    /// Set the phase offset of the task schedule (see [`time::Interval::set_phase`])
+    pub fn with_phase(mut self, phase: Duration) -> Self {
+        self.phase = phase;
+        self
+    }
+    /// Set the task deadline, relative to the scheduled release time. If not set, the period is
+    /// used
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+    /// Set the task thread scheduler and CPU affinity parameters
+    pub fn with_params(mut self, params: thread_rt::Params) -> Self {
+        self.params = params;
+        self
+    }
+    fn run(mut self, epoch: Monotonic, shutdown: &AtomicBool) -> TaskReport {
+        let deadline = self.deadline.unwrap_or(self.period);
+        let mut interval = time::interval(self.period)
+            .set_epoch(epoch)
+            .set_phase(self.phase)
+            .set_sleep_mode(SleepMode::Absolute);
+        let mut report = TaskReport {
+            name: mem::take(&mut self.name),
+            cycles: 0,
+            deadline_misses: 0,
+            max_exec_time: Duration::ZERO,
+            total_exec_time: Duration::ZERO,
+            max_response_time: Duration::ZERO,
+            interval_stats: IntervalStats::default(),
+        };
+        loop {
+            let tick = interval.tick();
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            let started = Monotonic::now();
+            (self.f)();
+            let exec_time = started.elapsed();
+            let response_time = tick.jitter() + exec_time;
+            report.cycles += 1;
+            report.total_exec_time += exec_time;
+            report.max_exec_time = report.max_exec_time.max(exec_time);
+            report.max_response_time = report.max_response_time.max(response_time);
+            if response_time > deadline {
+                report.deadline_misses += 1;
+                tracing::warn!(
+                    task = report.name,
+                    ?response_time,
+                    ?deadline,
+                    "task deadline missed"
+                );
+            }
+        }
+        report.interval_stats = interval.stats();
+        report
+    }
+}
+
+/// Per-task timing report
+#[derive(Debug, Clone)]
+pub struct TaskReport {
+    name: String,
+    cycles: u64,
+    deadline_misses: u64,
+    max_exec_time: Duration,
+    total_exec_time: Duration,
+    max_response_time: Duration,
+    interval_stats: IntervalStats,
+}
+
+impl TaskReport {
+    /// Task name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// The number of executed cycles
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+    /// The number of deadline misses
+    pub fn deadline_misses(&self) -> u64 {
+        self.deadline_misses
+    }
+    /// Maximal task function execution time
+    pub fn max_exec_time(&self) -> Duration {
+        self.max_exec_time
+    }
+    /// Mean task function execution time, None if no cycles have been executed
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn mean_exec_time(&self) -> Option<Duration> {
+        (self.cycles > 0).then(|| {
+            Duration::from_nanos((self.total_exec_time.as_nanos() / u128::from(self.cycles)) as u64)
+        })
+    }
+    /// Maximal response time: the time from the scheduled release to the task function completion
+    pub fn max_response_time(&self) -> Duration {
+        self.max_response_time
+    }
+    /// Wakeup latency and overrun statistics of the task interval
+    pub fn interval_stats(&self) -> &IntervalStats {
+        &self.interval_stats
+    }
+}
+
+/// A cyclic executive. Runs periodic tasks in dedicated real-time threads on intervals, aligned
+/// to a common epoch, and detects deadline misses
+#[derive(Default)]
+pub struct Scheduler {
+    tasks: Vec<Task>,
+}
+
+impl Scheduler {
+    /// Creates a new scheduler
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds a task. Can be used as a build pattern
+    pub fn with_task(mut self, task: Task) -> Self {
+        self.tasks.push(task);
+        self
+    }
+    /// Adds a task
+    pub fn add_task(&mut self, task: Task) {
+        self.tasks.push(task);
+    }
+    /// Spawns the task threads. The tasks are started only after the real-time parameters have
+    /// been successfully applied for all the threads. If the parameters can not be applied, the
+    /// already spawned threads are stopped and the error is returned
+    pub fn spawn(self) -> Result<RunningScheduler> {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let epoch = Monotonic::now();
+        let mut running = RunningScheduler {
+            shutdown: shutdown.clone(),
+            handles: Vec::with_capacity(self.tasks.len()),
+        };
+        let mut starters = Vec::with_capacity(self.tasks.len());
+        for task in self.tasks {
+            let (tx, rx) = oneshot::channel::<Result<()>, RawMutex, Condvar>();
+            let (start_tx, start_rx) = oneshot::channel::<bool, RawMutex, Condvar>();
+            let shutdown = shutdown.clone();
+            let name = task.name.clone();
+            let handle = thread::Builder::new()
+                .name(task.name.clone())
+                .spawn(move || {
+                    let result = thread_rt::apply_for_current(&task.params);
+                    let failed = result.is_err();
+                    let _ = tx.send(result);
+                    if failed || !start_rx.recv().unwrap_or_default() {
+                        return None;
+                    }
+                    Some(task.run(epoch, &shutdown))
+                })?;
+            running.handles.push((name, handle));
+            rx.recv().and_then(|r| r)?;
+            starters.push(start_tx);
+        }
+        for starter in starters {
+            starter.send(true)?;
+        }
+        Ok(running)
+    }
+}
+
+/// A running scheduler. Stops the tasks when dropped
+pub struct RunningScheduler {
+    shutdown: Arc<AtomicBool>,
+    handles: Vec<(String, thread::JoinHandle<Option<TaskReport>>)>,
+}
+
+impl RunningScheduler {
+    /// Stops the tasks, joins the threads and returns per-task timing reports. A task is stopped
+    /// on its next release, after the current cycle is completed
+    pub fn shutdown(mut self) -> Result<Vec<TaskReport>> {
+        self.stop()
+    }
+    fn stop(&mut self) -> Result<Vec<TaskReport>> {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let mut reports = Vec::with_capacity(self.handles.len());
+        let mut result = Ok(());
+        for (name, handle) in self.handles.drain(..) {
+            match handle.join() {
+                Ok(Some(report)) => reports.push(report),
+                Ok(None) => {}
+                Err(_) => {
+                    if result.is_ok() {
+                        result = Err(Error::Failed(format!("task {} panicked", name)));
+                    }
+                }
+            }
+        }
+        result.map(|()| reports)
+    }
+}
+
+impl Drop for RunningScheduler {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::{Scheduler, Task};
+
+    #[test]
+    fn test_scheduler() {
+        let fast = Arc::new(AtomicUsize::new(0));
+        let fast_c = fast.clone();
+        let running = Scheduler::new()
+            .with_task(Task::new("fast", Duration::from_millis(5), move || {
+                fast_c.fetch_add(1, Ordering::SeqCst);
+            }))
+            .with_task(
+                Task::new("slow", Duration::from_millis(20), || {
+                    thread::sleep(Duration::from_millis(2));
+                })
+                .with_phase(Duration::from_millis(1))
+                .with_deadline(Duration::from_micros(100)),
+            )
+            .spawn()
+            .unwrap();
+        thread::sleep(Duration::from_millis(100));
+        let reports = running.shutdown().unwrap();
+        let names: Vec<_> = reports.iter().map(|r| r.name()).collect();
+        assert_eq!(names, ["fast", "slow"]);
+        assert!(reports[0].cycles() > 5);
+        assert_eq!(
+            reports[0].cycles(),
+            u64::try_from(fast.load(Ordering::SeqCst)).unwrap()
+        );
+        let slow = &reports[1];
+        assert!(slow.cycles() > 1);
+        assert_eq!(slow.deadline_misses(), slow.cycles());
+        assert!(slow.max_exec_time() >= Duration::from_millis(2));
+        assert!(slow.mean_exec_time().unwrap() <= slow.max_exec_time());
+    }
+}
@@ -0,0 +1,461 @@
+use std::{
+    hint,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+use lock_api::{GuardSend, RawMutex as RawMutexTrait};
+
+use crate::condvar_api::{RawCondvar, WaitTimeoutResult};
+
+/// Default number of spin iterations before a [`HybridLock`]/[`HybridCondvar`] parks the thread
+pub const DEFAULT_SPINS: u32 = 1_000;
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+const CONTENDED: u32 = 2;
+
+#[cfg(target_os = "linux")]
+mod park {
+    use std::{sync::atomic::AtomicU32, time::Duration};
+
+    use linux_futex::{AsFutex as _, Futex, Private};
+
+    #[inline]
+    pub fn wait(value: &AtomicU32, expected: u32, timeout: Option<Duration>) {
+        let fx: &Futex<Private> = value.as_futex();
+        // all errors (interrupted, wrong value, timed out) are handled by the callers
+        if let Some(timeout) = timeout {
+            let _ = fx.wait_for(expected, timeout);
+        } else {
+            let _ = fx.wait(expected);
+        }
+    }
+
+    #[inline]
+    pub fn wake_one(value: &AtomicU32) {
+        let fx: &Futex<Private> = value.as_futex();
+        fx.wake(1);
+    }
+
+    #[inline]
+    pub fn wake_all(value: &AtomicU32) {
+        let fx: &Futex<Private> = value.as_futex();
+        fx.wake(i32::MAX);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod park {
+    use std::{sync::atomic::AtomicU32, thread, time::Duration};
+
+    #[inline]
+    pub fn wait(_value: &AtomicU32, _expected: u32, _timeout: Option<Duration>) {
+        thread::yield_now();
+    }
+
+    #[inline]
+    pub fn wake_one(_value: &AtomicU32) {}
+
+    #[inline]
+    pub fn wake_all(_value: &AtomicU32) {}
+}
+
+/// Spin-then-park lock. The lock spins for `SPINS` iterations and then falls back to the futex
+/// (Linux) or yields the thread (other platforms).
+///
+/// Suitable for latency-critical real-time tasks where the lock is held for very short periods
+/// of time. Note that the lock does not support priority inheritance.
+pub struct HybridLock<const SPINS: u32 = DEFAULT_SPINS> {
+    state: AtomicU32,
+}
+
+impl<const SPINS: u32> HybridLock<SPINS> {
+    #[inline]
+    fn spin_lock(&self) -> bool {
+        for _ in 0..SPINS {
+            if self.state.load(Ordering::Relaxed) == UNLOCKED
+                && self
+                    .state
+                    .compare_exchange_weak(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                return true;
+            }
+            hint::spin_loop();
+        }
+        false
+    }
+}
+
+unsafe impl<const SPINS: u32> RawMutexTrait for HybridLock<SPINS> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        state: AtomicU32::new(UNLOCKED),
+    };
+
+    type GuardMarker = GuardSend;
+
+    fn lock(&self) {
+        if self.spin_lock() {
+            return;
+        }
+        while self.state.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+            park::wait(&self.state, CONTENDED, None);
+        }
+    }
+
+    #[inline]
+    fn try_lock(&self) -> bool {
+        self.state
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+            park::wake_one(&self.state);
+        }
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.state.load(Ordering::Relaxed) != UNLOCKED
+    }
+}
+
+/// Spin-then-park condition variable for [`HybridLock`]. A waiter spins for `SPINS` iterations
+/// and then falls back to the futex (Linux) or yields the thread (other platforms).
+///
+/// Spinning waiters may be woken up spuriously by [`HybridCondvar::notify_one`].
+pub struct HybridCondvar<const SPINS: u32 = DEFAULT_SPINS> {
+    seq: AtomicU32,
+}
+
+impl<const SPINS: u32> Default for HybridCondvar<SPINS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SPINS: u32> HybridCondvar<SPINS> {
+    /// Creates a new condition variable
+    pub const fn new() -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+        }
+    }
+
+    fn wait_until<T>(
+        &self,
+        guard: &mut lock_api::MutexGuard<'_, HybridLock<SPINS>, T>,
+        deadline: Option<Instant>,
+    ) -> WaitTimeoutResult {
+        let seq = self.seq.load(Ordering::Acquire);
+        lock_api::MutexGuard::unlocked(guard, || {
+            for _ in 0..SPINS {
+                if self.seq.load(Ordering::Acquire) != seq {
+                    return WaitTimeoutResult::new(false);
+                }
+                hint::spin_loop();
+            }
+            loop {
+                if self.seq.load(Ordering::Acquire) != seq {
+                    return WaitTimeoutResult::new(false);
+                }
+                let timeout = if let Some(deadline) = deadline {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        return WaitTimeoutResult::new(true);
+                    };
+                    Some(remaining)
+                } else {
+                    None
+                };
+                park::wait(&self.seq, seq, timeout);
+            }
+        })
+    }
+
+    /// Notifies one thread waiting on this condvar
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::Release);
+        park::wake_one(&self.seq);
+    }
+
+    /// Notifies all threads waiting on this condvar
+    pub fn notify_all(&self) {
+        self.seq.fetch_add(1, Ordering::Release);
+        park::wake_all(&self.seq);
+    }
+}
+
+impl<const SPINS: u32> RawCondvar for HybridCondvar<SPINS> {
+    type RawMutex = HybridLock<SPINS>;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn wait<T, M>(&self, mutex_guard: &mut lock_api::MutexGuard<'_, Self::RawMutex, T>) {
+        self.wait_until(mutex_guard, None);
+    }
+
+    fn wait_for<T, M>(
+        &self,
+        mutex_guard: &mut lock_api::MutexGuard<'_, Self::RawMutex, T>,
+        timeout: Duration,
+    ) -> WaitTimeoutResult {
+        self.wait_until(mutex_guard, Some(Instant::now() + timeout))
+    }
+
+    fn notify_one(&self) {
+        self.notify_one();
+    }
+
+    fn notify_all(&self) {
+        self.notify_all();
+    }
+}
+
+/// Pure spin lock, which never parks the thread. Must be used on isolated CPU cores only, where
+/// all threads sharing the lock never get preempted by each other.
+pub struct SpinLock {
+    locked: AtomicU32,
+}
+
+unsafe impl RawMutexTrait for SpinLock {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        locked: AtomicU32::new(UNLOCKED),
+    };
+
+    type GuardMarker = GuardSend;
+
+    fn lock(&self) {
+        loop {
+            if self.try_lock() {
+                return;
+            }
+            while self.locked.load(Ordering::Relaxed) != UNLOCKED {
+                hint::spin_loop();
+            }
+        }
+    }
+
+    #[inline]
+    fn try_lock(&self) -> bool {
+        self.locked
+            .compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        self.locked.store(UNLOCKED, Ordering::Release);
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed) != UNLOCKED
+    }
+}
+
+/// Pure spin condition variable for [`SpinLock`], waiters never park the thread. Must be used
+/// on isolated CPU cores only.
+///
+/// Waiters may be woken up spuriously by [`SpinCondvar::notify_one`].
+#[derive(Default)]
+pub struct SpinCondvar {
+    seq: AtomicU32,
+}
+
+impl SpinCondvar {
+    /// Creates a new condition variable
+    pub const fn new() -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+        }
+    }
+
+    fn wait_until<T>(
+        &self,
+        guard: &mut lock_api::MutexGuard<'_, SpinLock, T>,
+        deadline: Option<Instant>,
+    ) -> WaitTimeoutResult {
+        let seq = self.seq.load(Ordering::Acquire);
+        lock_api::MutexGuard::unlocked(guard, || loop {
+            if self.seq.load(Ordering::Acquire) != seq {
+                return WaitTimeoutResult::new(false);
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return WaitTimeoutResult::new(true);
+            }
+            hint::spin_loop();
+        })
+    }
+
+    /// Notifies one thread waiting on this condvar
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::Release);
+    }
+
+    /// Notifies all threads waiting on this condvar
+    pub fn notify_all(&self) {
+        self.seq.fetch_add(1, Ordering::Release);
+    }
+}
+
+impl RawCondvar for SpinCondvar {
+    type RawMutex = SpinLock;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn wait<T, M>(&self, mutex_guard: &mut lock_api::MutexGuard<'_, Self::RawMutex, T>) {
+        self.wait_until(mutex_guard, None);
+    }
+
+    fn wait_for<T, M>(
+        &self,
+        mutex_guard: &mut lock_api::MutexGuard<'_, Self::RawMutex, T>,
+        timeout: Duration,
+    ) -> WaitTimeoutResult {
+        self.wait_until(mutex_guard, Some(Instant::now() + timeout))
+    }
+
+    fn notify_one(&self) {
+        self.notify_one();
+    }
+
+    fn notify_all(&self) {
+        self.notify_all();
+    }
+}
+
+/// Spin-then-park mutex
+pub type Mutex<T, const SPINS: u32 = DEFAULT_SPINS> = lock_api::Mutex<HybridLock<SPINS>, T>;
+/// Spin-then-park mutex guard
+pub type MutexGuard<'a, T, const SPINS: u32 = DEFAULT_SPINS> =
+    lock_api::MutexGuard<'a, HybridLock<SPINS>, T>;
+/// Spin-then-park raw mutex (compatibility name)
+pub type RawMutex = HybridLock;
+/// Spin-then-park condition variable (compatibility name)
+pub type Condvar = HybridCondvar;
+
+/// Pure spin mutex
+pub type SpinMutex<T> = lock_api::Mutex<SpinLock, T>;
+/// Pure spin mutex guard
+pub type SpinMutexGuard<'a, T> = lock_api::MutexGuard<'a, SpinLock, T>;
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, thread, time::Duration};
+
+    use super::{HybridCondvar, HybridLock, Mutex, SpinCondvar, SpinLock, SpinMutex};
+    use crate::{cell::DataCell, channel, semaphore::Semaphore};
+
+    const NUM_THREADS: usize = 10;
+    const ITERS: usize = 1_000;
+
+    #[test]
+    fn test_hybrid_mutex() {
+        let mutex: Arc<Mutex<usize, 10>> = Arc::new(Mutex::new(0));
+        let handles: Vec<_> = (0..NUM_THREADS)
+            .map(|_| {
+                let m = mutex.clone();
+                thread::spawn(move || {
+                    for _ in 0..ITERS {
+                        *m.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*mutex.lock(), NUM_THREADS * ITERS);
+    }
+
+    #[test]
+    fn test_spin_mutex() {
+        let mutex: Arc<SpinMutex<usize>> = Arc::new(SpinMutex::new(0));
+        let handles: Vec<_> = (0..NUM_THREADS)
+            .map(|_| {
+                let m = mutex.clone();
+                thread::spawn(move || {
+                    for _ in 0..ITERS {
+                        *m.lock() += 1;
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(*mutex.lock(), NUM_THREADS * ITERS);
+    }
+
+    #[test]
+    fn test_hybrid_channel() {
+        let (tx, rx) = channel::bounded::<usize, HybridLock<10>, HybridCondvar<10>>(1);
+        let handle = thread::spawn(move || {
+            for i in 0..ITERS {
+                tx.send(i).unwrap();
+            }
+        });
+        for i in 0..ITERS {
+            assert_eq!(rx.recv().unwrap(), i);
+        }
+        handle.join().unwrap();
+        assert!(rx.recv().is_err());
+        assert!(matches!(
+            rx.recv_timeout(Duration::from_millis(10)),
+            Err(crate::Error::ChannelClosed)
+        ));
+    }
+
+    #[test]
+    fn test_spin_channel() {
+        let (tx, rx) = channel::bounded::<usize, SpinLock, SpinCondvar>(1);
+        let handle = thread::spawn(move || {
+            for i in 0..ITERS {
+                tx.send(i).unwrap();
+            }
+        });
+        for i in 0..ITERS {
+            assert_eq!(rx.recv().unwrap(), i);
+        }
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_hybrid_datacell_timeout() {
+        let cell: DataCell<usize, HybridLock, HybridCondvar> = DataCell::new();
+        assert!(matches!(
+            cell.get_timeout(Duration::from_millis(10)),
+            Err(crate::Error::Timeout)
+        ));
+        let cell2 = cell.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            cell2.set(42);
+        });
+        assert_eq!(cell.get().unwrap(), 42);
+    }
+
+    #[test]
+    fn test_spin_semaphore() {
+        let sem: Arc<Semaphore<SpinLock, SpinCondvar>> = Arc::new(Semaphore::new(1));
+        let guard = sem.acquire();
+        let sem2 = sem.clone();
+        let handle = thread::spawn(move || {
+            let _g = sem2.acquire();
+        });
+        thread::sleep(Duration::from_millis(10));
+        drop(guard);
+        handle.join().unwrap();
+        assert_eq!(sem.available(), 1);
+    }
+}
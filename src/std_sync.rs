@@ -0,0 +1,155 @@
+use std::{
+    sync::{self, PoisonError},
+    time::{Duration, Instant},
+};
+
+use lock_api::{GuardSend, RawMutex as RawMutexTrait};
+
+use crate::condvar_api::{RawCondvar, WaitTimeoutResult};
+
+/// A raw mutex, built on top of [`std::sync::Mutex`] and [`std::sync::Condvar`]. Allows to use
+/// the crate components with the standard library synchronization primitives only.
+///
+/// Poisoning is ignored, as the protected state is a single flag which can not be corrupted.
+///
+/// For a lightweight futex-based mutex without priority inheritance consider
+/// [`crate::spin::HybridLock`] with zero spins.
+pub struct StdLock {
+    locked: sync::Mutex<bool>,
+    unlocked: sync::Condvar,
+}
+
+unsafe impl RawMutexTrait for StdLock {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        locked: sync::Mutex::new(false),
+        unlocked: sync::Condvar::new(),
+    };
+
+    type GuardMarker = GuardSend;
+
+    fn lock(&self) {
+        let mut locked = self.locked.lock().unwrap_or_else(PoisonError::into_inner);
+        while *locked {
+            locked = self
+                .unlocked
+                .wait(locked)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *locked = true;
+    }
+
+    fn try_lock(&self) -> bool {
+        let mut locked = self.locked.lock().unwrap_or_else(PoisonError::into_inner);
+        if *locked {
+            false
+        } else {
+            *locked = true;
+            true
+        }
+    }
+
+    unsafe fn unlock(&self) {
+        *self.locked.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        self.unlocked.notify_one();
+    }
+
+    fn is_locked(&self) -> bool {
+        *self.locked.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A condition variable for [`StdLock`], built on top of [`std::sync::Condvar`]
+#[derive(Default)]
+pub struct Condvar {
+    seq: sync::Mutex<u64>,
+    cv: sync::Condvar,
+}
+
+impl Condvar {
+    /// Creates a new condition variable
+    pub const fn new() -> Self {
+        Self {
+            seq: sync::Mutex::new(0),
+            cv: sync::Condvar::new(),
+        }
+    }
+
+    fn wait_until<T>(
+        &self,
+        mutex_guard: &mut lock_api::MutexGuard<'_, StdLock, T>,
+        deadline: Option<Instant>,
+    ) -> WaitTimeoutResult {
+        // the sequence lock is taken before the mutex is unlocked, so a notification, sent by a
+        // thread which has locked the mutex after, can not be lost
+        let mut seq_guard = self.seq.lock().unwrap_or_else(PoisonError::into_inner);
+        let seq = *seq_guard;
+        lock_api::MutexGuard::unlocked(mutex_guard, move || {
+            while *seq_guard == seq {
+                if let Some(deadline) = deadline {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        return WaitTimeoutResult::new(true);
+                    };
+                    seq_guard = self
+                        .cv
+                        .wait_timeout(seq_guard, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                } else {
+                    seq_guard = self
+                        .cv
+                        .wait(seq_guard)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+            WaitTimeoutResult::new(false)
+        })
+    }
+
+    /// Notifies one thread waiting on this condvar
+    pub fn notify_one(&self) {
+        *self.seq.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+        self.cv.notify_one();
+    }
+
+    /// Notifies all threads waiting on this condvar
+    pub fn notify_all(&self) {
+        *self.seq.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+        self.cv.notify_all();
+    }
+}
+
+impl RawCondvar for Condvar {
+    type RawMutex = StdLock;
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn wait<T, M>(&self, mutex_guard: &mut lock_api::MutexGuard<'_, Self::RawMutex, T>) {
+        self.wait_until(mutex_guard, None);
+    }
+
+    fn wait_for<T, M>(
+        &self,
+        mutex_guard: &mut lock_api::MutexGuard<'_, Self::RawMutex, T>,
+        timeout: Duration,
+    ) -> WaitTimeoutResult {
+        self.wait_until(mutex_guard, Some(Instant::now() + timeout))
+    }
+
+    fn notify_one(&self) {
+        self.notify_one();
+    }
+
+    fn notify_all(&self) {
+        self.notify_all();
+    }
+}
+
+/// std-based mutex
+pub type Mutex<T> = lock_api::Mutex<StdLock, T>;
+/// std-based mutex guard
+pub type MutexGuard<'a, T> = lock_api::MutexGuard<'a, StdLock, T>;
+/// std-based raw mutex (compatibility name)
+pub type RawMutex = StdLock;
@@ -0,0 +1,324 @@
+use std::{thread, time::Duration};
+
+use crate::{
+    base_channel::{make_channel, BaseChannel, ChannelStorage},
+    data_policy::{DataDeliveryPolicy, DeliveryPolicy, StorageTryPushOutput},
+    locking::{Condvar, RawMutex},
+    Error,
+};
+
+const CAPACITY: usize = 4;
+const SENDERS: usize = 4;
+const RECEIVERS: usize = 2;
+const ITERS: usize = 250;
+const TIMEOUT: Duration = Duration::from_millis(10);
+
+/// A message with various delivery policies, used by [`check_policy_storage`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TestMessage {
+    /// [`DeliveryPolicy::Always`] with the given priority
+    Always(usize, usize),
+    /// [`DeliveryPolicy::Latest`]
+    Latest(usize),
+    /// [`DeliveryPolicy::Optional`]
+    Optional(usize),
+    /// [`DeliveryPolicy::Single`]
+    Single(usize),
+    /// [`DeliveryPolicy::SingleOptional`]
+    SingleOptional(usize),
+    /// An expired message
+    Expired,
+}
+
+impl DataDeliveryPolicy for TestMessage {
+    fn delivery_policy(&self) -> DeliveryPolicy {
+        match self {
+            TestMessage::Always(..) | TestMessage::Expired => DeliveryPolicy::Always,
+            TestMessage::Latest(_) => DeliveryPolicy::Latest,
+            TestMessage::Optional(_) => DeliveryPolicy::Optional,
+            TestMessage::Single(_) => DeliveryPolicy::Single,
+            TestMessage::SingleOptional(_) => DeliveryPolicy::SingleOptional,
+        }
+    }
+    fn priority(&self) -> usize {
+        match self {
+            TestMessage::Always(_, priority) => *priority,
+            _ => 100,
+        }
+    }
+    fn is_expired(&self) -> bool {
+        matches!(self, TestMessage::Expired)
+    }
+}
+
+macro_rules! assert_pushed {
+    ($storage: expr, $value: expr) => {
+        assert!(
+            matches!($storage.try_push($value), StorageTryPushOutput::Pushed),
+            "the value must be pushed"
+        );
+    };
+}
+
+fn assert_len<T, S: ChannelStorage<T>>(storage: &S, len: usize) {
+    assert_eq!(storage.len(), len, "invalid storage length");
+    assert_eq!(storage.is_empty(), len == 0, "invalid is_empty result");
+    assert_eq!(storage.is_full(), len == CAPACITY, "invalid is_full result");
+}
+
+/// Checks the basic storage invariants (`try_push`/`get`/`len`/`is_full`/`is_empty`, FIFO
+/// ordering) and the blocking behaviour of a channel with the storage under concurrency.
+///
+/// Example:
+///
+/// ```rust,ignore
+/// #[test]
+/// fn test_my_storage() {
+///     rtsc::testing::check_storage::<MyStorage<usize>>();
+///     rtsc::testing::check_policy_storage::<MyStorage<rtsc::testing::TestMessage>>();
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Will panic if the storage does not conform
+pub fn check_storage<S>()
+where
+    S: ChannelStorage<usize> + Send + 'static,
+{
+    check_storage_invariants::<S>();
+    check_storage_blocking::<S>();
+    check_storage_concurrency::<S>();
+}
+
+fn check_storage_invariants<S: ChannelStorage<usize>>() {
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, false);
+    assert_len(&storage, 0);
+    assert!(storage.get().is_none(), "an empty storage must return None");
+    for i in 0..CAPACITY {
+        assert_pushed!(storage, i);
+        assert_len(&storage, i + 1);
+    }
+    match storage.try_push(CAPACITY) {
+        StorageTryPushOutput::Full(v) => {
+            assert_eq!(v, CAPACITY, "a full storage must return the value back");
+        }
+        _ => panic!("a full storage must return Full"),
+    }
+    assert_len(&storage, CAPACITY);
+    for i in 0..CAPACITY {
+        assert_eq!(storage.get(), Some(i), "invalid storage ordering");
+        assert_len(&storage, CAPACITY - i - 1);
+    }
+    assert!(storage.get().is_none(), "an empty storage must return None");
+    // wrap-around
+    for round in 0..CAPACITY * 2 {
+        assert_pushed!(storage, round);
+        assert_pushed!(storage, round + 1);
+        assert_eq!(storage.get(), Some(round));
+        assert_eq!(storage.get(), Some(round + 1));
+    }
+    assert_len(&storage, 0);
+}
+
+fn check_storage_blocking<S>()
+where
+    S: ChannelStorage<usize> + Send + 'static,
+{
+    let (tx, rx) = make_channel(BaseChannel::<usize, S, RawMutex, Condvar>::new(
+        CAPACITY, false,
+    ));
+    assert!(matches!(rx.try_recv(), Err(Error::ChannelEmpty)));
+    assert!(matches!(rx.recv_timeout(TIMEOUT), Err(Error::Timeout)));
+    for i in 0..CAPACITY {
+        tx.send(i).unwrap();
+    }
+    assert!(tx.is_full());
+    assert!(matches!(tx.try_send(CAPACITY), Err(Error::ChannelFull)));
+    assert!(matches!(
+        tx.send_timeout(CAPACITY, TIMEOUT),
+        Err(Error::Timeout)
+    ));
+    // a blocked sender must be woken up when a value is received
+    let handle = thread::spawn(move || {
+        tx.send(CAPACITY).unwrap();
+    });
+    thread::sleep(TIMEOUT);
+    assert_eq!(rx.recv().unwrap(), 0);
+    handle.join().unwrap();
+    for i in 1..=CAPACITY {
+        assert_eq!(rx.recv().unwrap(), i);
+    }
+    // the sender is dropped
+    assert!(matches!(rx.recv(), Err(Error::ChannelClosed)));
+    // a blocked receiver must be woken up when a value is sent
+    let (tx, rx) = make_channel(BaseChannel::<usize, S, RawMutex, Condvar>::new(
+        CAPACITY, false,
+    ));
+    let handle = thread::spawn(move || rx.recv());
+    thread::sleep(TIMEOUT);
+    tx.send(42).unwrap();
+    assert_eq!(handle.join().unwrap().unwrap(), 42);
+    assert!(matches!(tx.send(1), Err(Error::ChannelClosed)));
+}
+
+fn check_storage_concurrency<S>()
+where
+    S: ChannelStorage<usize> + Send + 'static,
+{
+    let (tx, rx) = make_channel(BaseChannel::<usize, S, RawMutex, Condvar>::new(
+        CAPACITY, false,
+    ));
+    let senders: Vec<_> = (0..SENDERS)
+        .map(|s| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in 0..ITERS {
+                    tx.send(s * ITERS + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+    let receivers: Vec<_> = (0..RECEIVERS)
+        .map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                while let Ok(v) = rx.recv() {
+                    received.push(v);
+                }
+                received
+            })
+        })
+        .collect();
+    drop(rx);
+    for sender in senders {
+        sender.join().unwrap();
+    }
+    let mut received = Vec::with_capacity(SENDERS * ITERS);
+    for receiver in receivers {
+        let r = receiver.join().unwrap();
+        // values from a single sender must arrive in order
+        for s in 0..SENDERS {
+            let from_sender: Vec<_> = r.iter().filter(|v| **v / ITERS == s).collect();
+            assert!(
+                from_sender.windows(2).all(|w| w[0] < w[1]),
+                "values from a single sender are not ordered"
+            );
+        }
+        received.extend(r);
+    }
+    received.sort_unstable();
+    assert_eq!(
+        received,
+        (0..SENDERS * ITERS).collect::<Vec<_>>(),
+        "values are lost or duplicated"
+    );
+}
+
+/// Checks [`DataDeliveryPolicy`] semantics of a policy-aware storage: delivery policies,
+/// expiration and priority ordering.
+///
+/// # Panics
+///
+/// Will panic if the storage does not conform
+pub fn check_policy_storage<S>()
+where
+    S: ChannelStorage<TestMessage>,
+{
+    // optional values are skipped if there is no room
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, false);
+    for i in 0..CAPACITY {
+        assert_pushed!(storage, TestMessage::Always(i, 100));
+    }
+    assert!(
+        matches!(
+            storage.try_push(TestMessage::Optional(0)),
+            StorageTryPushOutput::Skipped
+        ),
+        "an optional value must be skipped if the storage is full"
+    );
+    assert!(
+        matches!(
+            storage.try_push(TestMessage::SingleOptional(0)),
+            StorageTryPushOutput::Skipped
+        ),
+        "a single-optional value must be skipped if the storage is full"
+    );
+    assert!(matches!(
+        storage.try_push(TestMessage::Always(CAPACITY, 100)),
+        StorageTryPushOutput::Full(_)
+    ));
+    // optional values are replaced by mandatory ones
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, false);
+    assert_pushed!(storage, TestMessage::Optional(0));
+    for i in 1..CAPACITY {
+        assert_pushed!(storage, TestMessage::Always(i, 100));
+    }
+    assert_pushed!(storage, TestMessage::Always(CAPACITY, 100));
+    assert_len(&storage, CAPACITY);
+    assert_eq!(storage.get(), Some(TestMessage::Always(1, 100)));
+    // single values are kept in a single copy
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, false);
+    assert_pushed!(storage, TestMessage::Single(1));
+    assert_pushed!(storage, TestMessage::Always(0, 100));
+    assert_pushed!(storage, TestMessage::Single(2));
+    assert_eq!(
+        storage.len(),
+        2,
+        "a single value must replace the previous one"
+    );
+    assert_eq!(storage.get(), Some(TestMessage::Always(0, 100)));
+    assert_eq!(storage.get(), Some(TestMessage::Single(2)));
+    // latest values replace the previous ones of the same kind if there is no room
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, false);
+    for i in 0..CAPACITY {
+        assert_pushed!(storage, TestMessage::Latest(i));
+    }
+    assert_pushed!(storage, TestMessage::Latest(CAPACITY));
+    assert_len(&storage, CAPACITY);
+    assert_eq!(storage.get(), Some(TestMessage::Latest(1)));
+    // expired values are not delivered
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, false);
+    assert_pushed!(storage, TestMessage::Expired);
+    assert!(
+        storage.get().is_none(),
+        "an expired value must not be delivered"
+    );
+    // priority ordering
+    let mut storage = S::with_capacity_and_ordering(CAPACITY, true);
+    assert_pushed!(storage, TestMessage::Always(0, 100));
+    assert_pushed!(storage, TestMessage::Always(1, 10));
+    assert_pushed!(storage, TestMessage::Always(2, 50));
+    assert_pushed!(storage, TestMessage::Always(3, 10));
+    assert_eq!(storage.get(), Some(TestMessage::Always(1, 10)));
+    assert_eq!(storage.get(), Some(TestMessage::Always(3, 10)));
+    assert_eq!(storage.get(), Some(TestMessage::Always(2, 50)));
+    assert_eq!(storage.get(), Some(TestMessage::Always(0, 100)));
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use crate::{growable, pdeque};
+
+    use super::{check_policy_storage, check_storage, TestMessage};
+
+    #[test]
+    fn test_vecdeque() {
+        check_storage::<VecDeque<usize>>();
+    }
+
+    #[test]
+    fn test_growable() {
+        check_storage::<growable::Deque<usize>>();
+    }
+
+    #[test]
+    fn test_pdeque() {
+        check_storage::<pdeque::Deque<usize>>();
+        check_policy_storage::<pdeque::Deque<TestMessage>>();
+    }
+}
@@ -0,0 +1,161 @@
+use std::thread;
+
+use super::{apply_for_current, os, Params};
+use crate::{
+    locking::{Condvar, RawMutex},
+    oneshot, Error, Result,
+};
+
+const PREFAULT_CHUNK: usize = 4096;
+// the stack used by the thread start-up code, the last prefault chunk and call frames
+const PREFAULT_MARGIN: usize = 32 * 1024;
+
+/// Real-time thread factory. Spawns a thread and applies the scheduler and CPU affinity
+/// parameters before the user code is started. The result of the parameters application is
+/// returned to the spawner.
+///
+/// Example:
+///
+/// ```rust,no_run
+/// use rtsc::thread_rt::{Builder, Params, Scheduling};
+///
+/// let handle = Builder::new()
+///     .with_name("worker")
+///     .with_params(Params::new().with_priority(Some(80)).with_scheduling(Scheduling::FIFO))
+///     .with_stack_size(256 * 1024)
+///     .with_prefault_stack(64 * 1024)
+///     .spawn(|| 42)
+///     .expect("Unable to spawn a real-time thread");
+/// println!("worker tid: {}", handle.tid());
+/// assert_eq!(handle.join().unwrap(), 42);
+/// ```
+#[derive(Default, Clone)]
+pub struct Builder {
+    name: Option<String>,
+    stack_size: Option<usize>,
+    params: Params,
+    prefault_stack: usize,
+}
+
+impl Builder {
+    /// Creates a new builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Set the thread name
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+    /// Set the thread stack size
+    pub fn with_stack_size(mut self, stack_size: usize) -> Self {
+        self.stack_size = Some(stack_size);
+        self
+    }
+    /// Set the thread scheduler and CPU affinity parameters
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+    /// Touch the given number of bytes of the thread stack before the user code is started, so
+    /// the stack pages are mapped in advance (locked if `mlockall` is active, see
+    /// [`super::preallocate_heap`]). Requires the stack size to be set explicitly, the prefault
+    /// size must be at least 32 KiB less than the stack size
+    pub fn with_prefault_stack(mut self, size: usize) -> Self {
+        self.prefault_stack = size;
+        self
+    }
+    /// Spawns the thread. Returns an error if the thread can not be spawned or the parameters can
+    /// not be applied (in the latter case the user code is not started)
+    pub fn spawn<F, T>(self, f: F) -> Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        if self.prefault_stack > 0
+            && self.stack_size.is_none_or(|stack_size| {
+                self.prefault_stack.saturating_add(PREFAULT_MARGIN) > stack_size
+            })
+        {
+            return Err(Error::InvalidData(
+                "the prefault size must be at least 32 KiB less than the explicitly set stack size"
+                    .to_owned(),
+            ));
+        }
+        self.params.validate()?;
+        let mut builder = thread::Builder::new();
+        if let Some(name) = self.name {
+            builder = builder.name(name);
+        }
+        if let Some(stack_size) = self.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        let (tx, rx) = oneshot::channel::<(libc::c_int, Result<()>), RawMutex, Condvar>();
+        let params = self.params;
+        let prefault_stack = self.prefault_stack;
+        let handle = builder.spawn(move || {
+            let result = apply_for_current(&params);
+            let failed = result.is_err();
+            if !failed && prefault_stack > 0 {
+                let top = std::hint::black_box(0u8);
+                prefault((&raw const top as usize).saturating_sub(prefault_stack));
+            }
+            let _ = tx.send((os::current_tid(), result));
+            if failed {
+                return None;
+            }
+            Some(f())
+        })?;
+        match rx.recv() {
+            Ok((tid, Ok(()))) => Ok(JoinHandle { handle, tid }),
+            Ok((_, Err(e))) | Err(e) => {
+                let _ = handle.join();
+                Err(e)
+            }
+        }
+    }
+}
+
+// touches the stack chunk by chunk down to the given address (the stack grows downwards on all
+// supported platforms). The actual chunk addresses are checked, so call frame overhead is taken
+// into account
+#[inline(never)]
+fn prefault(bottom: usize) {
+    let mut chunk = [0u8; PREFAULT_CHUNK];
+    std::hint::black_box(&mut chunk);
+    if chunk.as_ptr() as usize > bottom {
+        prefault(bottom);
+    }
+}
+
+/// A handle of a thread, spawned with [`Builder`]
+pub struct JoinHandle<T> {
+    handle: thread::JoinHandle<Option<T>>,
+    tid: libc::c_int,
+}
+
+impl<T> JoinHandle<T> {
+    /// The thread OS id, can be used to change the thread parameters later with [`super::apply`].
+    /// Zero on unsupported platforms
+    pub fn tid(&self) -> libc::c_int {
+        self.tid
+    }
+    /// The thread object
+    pub fn thread(&self) -> &thread::Thread {
+        self.handle.thread()
+    }
+    /// Checks if the thread has finished
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+    /// Waits for the thread to finish and returns its result
+    ///
+    /// # Panics
+    ///
+    /// Should not panic
+    pub fn join(self) -> thread::Result<T> {
+        self.handle
+            .join()
+            .map(|v| v.expect("the user code is started only if the parameters are applied"))
+    }
+}
@@ -0,0 +1,59 @@
+use std::io;
+
+use super::Capabilities;
+use crate::Result;
+
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+const CAP_IPC_LOCK: u32 = 14;
+const CAP_SYS_NICE: u32 = 23;
+
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+#[allow(clippy::useless_conversion)]
+pub fn capabilities() -> Result<Capabilities> {
+    let mut header = CapHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapData::default(); 2];
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_capget,
+            &mut header as *mut CapHeader,
+            data.as_mut_ptr(),
+        )
+    };
+    if res != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    let has_cap = |cap: u32| data[(cap / 32) as usize].effective & (1 << (cap % 32)) != 0;
+    let rlimit = |resource| {
+        let mut limit: libc::rlimit = unsafe { std::mem::zeroed() };
+        if unsafe { libc::getrlimit(resource, &mut limit) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(if limit.rlim_cur == libc::RLIM_INFINITY {
+            u64::MAX
+        } else {
+            u64::from(limit.rlim_cur)
+        })
+    };
+    Ok(Capabilities {
+        sys_nice: has_cap(CAP_SYS_NICE),
+        ipc_lock: has_cap(CAP_IPC_LOCK),
+        rtprio_limit: rlimit(libc::RLIMIT_RTPRIO)?,
+        memlock_limit: rlimit(libc::RLIMIT_MEMLOCK)?,
+    })
+}
@@ -1,29 +1,14 @@
 use super::{Params, Scheduling};
 use crate::{Error, Result};
-
-impl From<Scheduling> for libc::c_int {
-    fn from(value: Scheduling) -> Self {
-        match value {
-            Scheduling::RoundRobin => libc::SCHED_RR,
-            Scheduling::FIFO => libc::SCHED_FIFO,
-            Scheduling::Idle => libc::SCHED_IDLE,
-            Scheduling::Batch => libc::SCHED_BATCH,
-            Scheduling::DeadLine => libc::SCHED_DEADLINE,
-            Scheduling::Other => libc::SCHED_NORMAL,
-        }
-    }
-}
+use std::io;
 
 pub fn apply_for_current(params: &Params) -> Result<()> {
     apply(0, params)
 }
 
 pub fn apply(tid: libc::c_int, params: &Params) -> Result<()> {
-    let user_id = unsafe { libc::getuid() };
+    capabilities()?.check(params)?;
     if !params.cpu_ids.is_empty() {
-        if user_id != 0 {
-            return Err(Error::AccessDenied);
-        }
         unsafe {
             let mut cpuset: libc::cpu_set_t = std::mem::zeroed();
             for cpu in &params.cpu_ids {
@@ -31,15 +16,18 @@ pub fn apply(tid: libc::c_int, params: &Params) -> Result<()> {
             }
             let res = libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &cpuset);
             if res != 0 {
-                return Err(Error::RTSchedSetAffinity(res.to_string()));
+                return Err(Error::RTSchedSetAffinity(
+                    io::Error::last_os_error().to_string(),
+                ));
             }
         }
     }
-    if let Some(priority) = params.priority {
-        if user_id != 0 {
-            return Err(Error::AccessDenied);
-        }
-        let sched = if priority == 0 {
+    if let Some(deadline) = params.deadline {
+        super::sched_attr::set_deadline(tid, &deadline)?;
+    } else if let Some(priority) = params.effective_priority() {
+        let sched = if priority == 0
+            && matches!(params.scheduling, Scheduling::RoundRobin | Scheduling::FIFO)
+        {
             Scheduling::Other
         } else {
             params.scheduling
@@ -54,18 +42,22 @@ pub fn apply(tid: libc::c_int, params: &Params) -> Result<()> {
             )
         };
         if res != 0 {
-            return Err(Error::RTSchedSetScheduler(res.to_string()));
+            return Err(Error::RTSchedSetScheduler(
+                io::Error::last_os_error().to_string(),
+            ));
         }
     }
     Ok(())
 }
 
+pub use super::caps::capabilities;
+pub use super::sched_attr::{current_tid, get};
+
 pub fn prealloc_heap(size: usize) -> Result<()> {
     if size == 0 {
         return Ok(());
     }
-    let user_id = unsafe { libc::getuid() };
-    if user_id != 0 {
+    if !capabilities()?.can_lock_memory(size) {
         return Err(Error::AccessDenied);
     }
     let page_size = unsafe {
@@ -78,7 +70,10 @@ pub fn prealloc_heap(size: usize) -> Result<()> {
             return Err(Error::Failed("unable to disable trimming".to_owned()));
         }
         if libc::mlockall(libc::MCL_FUTURE) == -1 {
-            return Err(Error::Failed("unable to lock memory pages".to_owned()));
+            return Err(Error::Failed(format!(
+                "unable to lock memory pages: {}",
+                io::Error::last_os_error()
+            )));
         };
         usize::try_from(libc::sysconf(libc::_SC_PAGESIZE)).expect("Page size too large")
     };
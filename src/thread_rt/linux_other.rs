@@ -1,87 +1,98 @@
 use super::{Params, Scheduling};
 use crate::{Error, Result};
-use std::process::Stdio;
-
-struct ChrtSchedArgument(&'static str);
-
-impl From<Scheduling> for ChrtSchedArgument {
-    fn from(value: Scheduling) -> Self {
-        match value {
-            Scheduling::RoundRobin => ChrtSchedArgument("--rr"),
-            Scheduling::FIFO => ChrtSchedArgument("--fifo"),
-            Scheduling::Idle => ChrtSchedArgument("--idle"),
-            Scheduling::Batch => ChrtSchedArgument("--batch"),
-            Scheduling::DeadLine => ChrtSchedArgument("--deadline"),
-            Scheduling::Other => ChrtSchedArgument("--other"),
-        }
-    }
-}
+use std::io;
 
+// non-glibc libc implementations (e.g. musl) may not provide working scheduler wrappers, so the
+// raw syscalls are used
 pub fn apply_for_current(params: &Params) -> Result<()> {
-    let tid = unsafe { i32::try_from(libc::syscall(libc::SYS_gettid)) }
-        .map_err(|e| Error::Failed(e.to_string()))?;
-    apply(tid, params)
+    apply(0, params)
 }
 
 pub fn apply(tid: libc::c_int, params: &Params) -> Result<()> {
-    let user_id = unsafe { libc::getuid() };
+    capabilities()?.check(params)?;
     if !params.cpu_ids.is_empty() {
-        if user_id != 0 {
-            return Err(Error::AccessDenied);
-        }
-        let result = std::process::Command::new("taskset")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .arg("-cp")
-            .arg(
-                &params
-                    .cpu_ids
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<_>>()
-                    .join(","),
+        let res = unsafe {
+            let mut cpuset: libc::cpu_set_t = std::mem::zeroed();
+            for cpu in &params.cpu_ids {
+                libc::CPU_SET(*cpu, &mut cpuset);
+            }
+            libc::syscall(
+                libc::SYS_sched_setaffinity,
+                tid,
+                std::mem::size_of::<libc::cpu_set_t>(),
+                &cpuset as *const libc::cpu_set_t,
             )
-            .arg(tid.to_string())
-            .status()
-            .map_err(|e| Error::RTSchedSetAffinity(e.to_string()))?;
-        if !result.success() {
-            return Err(Error::RTSchedSetAffinity(format!(
-                "taskset exit code {}",
-                result.code().unwrap_or(-1)
-            )));
+        };
+        if res != 0 {
+            return Err(Error::RTSchedSetAffinity(
+                io::Error::last_os_error().to_string(),
+            ));
         }
     }
-    if let Some(priority) = params.priority {
-        if user_id != 0 {
-            return Err(Error::AccessDenied);
-        }
-        let sched = if priority == 0 {
+    if let Some(deadline) = params.deadline {
+        super::sched_attr::set_deadline(tid, &deadline)?;
+    } else if let Some(priority) = params.effective_priority() {
+        let sched = if priority == 0
+            && matches!(params.scheduling, Scheduling::RoundRobin | Scheduling::FIFO)
+        {
             Scheduling::Other
         } else {
             params.scheduling
         };
-        let result = std::process::Command::new("chrt")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .arg(ChrtSchedArgument::from(sched).0)
-            .arg("-p")
-            .arg(priority.to_string())
-            .arg(tid.to_string())
-            .status()
-            .map_err(|e| Error::RTSchedSetScheduler(e.to_string()))?;
-        if !result.success() {
-            return Err(Error::RTSchedSetScheduler(format!(
-                "chrt exit code {}",
-                result.code().unwrap_or(-1)
-            )));
+        let param = libc::sched_param {
+            sched_priority: priority,
+        };
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_sched_setscheduler,
+                tid,
+                libc::c_int::from(sched),
+                &param as *const libc::sched_param,
+            )
+        };
+        if res != 0 {
+            return Err(Error::RTSchedSetScheduler(
+                io::Error::last_os_error().to_string(),
+            ));
         }
     }
     Ok(())
 }
 
+pub use super::caps::capabilities;
+pub use super::sched_attr::{current_tid, get};
+
+// without mallopt the allocator may return freed memory to the system at any time, so a region of
+// the requested size is mapped, populated and locked, and kept for the process lifetime. All
+// pages, mapped in future, are locked as well
 pub fn prealloc_heap(size: usize) -> Result<()> {
     if size == 0 {
         return Ok(());
     }
-    Err(Error::Unimplemented)
+    if !capabilities()?.can_lock_memory(size) {
+        return Err(Error::AccessDenied);
+    }
+    if unsafe { libc::mlockall(libc::MCL_FUTURE) } == -1 {
+        return Err(Error::Failed(format!(
+            "unable to lock memory pages: {}",
+            io::Error::last_os_error()
+        )));
+    }
+    let region = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            size,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE | libc::MAP_LOCKED,
+            -1,
+            0,
+        )
+    };
+    if region == libc::MAP_FAILED {
+        return Err(Error::Failed(format!(
+            "unable to preallocate memory: {}",
+            io::Error::last_os_error()
+        )));
+    }
+    Ok(())
 }
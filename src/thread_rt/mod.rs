@@ -1,4 +1,14 @@
-use crate::Result;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+mod builder;
+#[cfg(target_os = "linux")]
+mod caps;
+#[cfg(target_os = "linux")]
+mod sched_attr;
+
+pub use builder::{Builder, JoinHandle};
 
 #[cfg(all(target_os = "linux", target_env = "gnu"))]
 #[path = "linux_gnu.rs"]
@@ -21,6 +31,8 @@ pub struct Params {
     scheduling: Scheduling,
     /// CPU affinity
     cpu_ids: Vec<usize>,
+    /// `SCHED_DEADLINE` parameters
+    deadline: Option<DeadlineParams>,
 }
 
 impl Params {
@@ -33,7 +45,8 @@ impl Params {
         self.priority = priority;
         self
     }
-    /// Set the thread scheduler policy
+    /// Set the thread scheduler policy. [`Scheduling::Batch`] and [`Scheduling::Idle`] do not
+    /// require a priority, real-time policies do
     pub fn with_scheduling(mut self, scheduling: Scheduling) -> Self {
         self.scheduling = scheduling;
         self
@@ -43,6 +56,13 @@ impl Params {
         self.cpu_ids = cpu_ids.to_vec();
         self
     }
+    /// Set `SCHED_DEADLINE` parameters. The scheduling policy is set to
+    /// [`Scheduling::DeadLine`]
+    pub fn with_deadline(mut self, deadline: DeadlineParams) -> Self {
+        self.scheduling = Scheduling::DeadLine;
+        self.deadline = Some(deadline);
+        self
+    }
     /// Get the thread priority
     pub fn priority(&self) -> Option<i32> {
         self.priority
@@ -55,6 +75,124 @@ impl Params {
     pub fn cpu_ids(&self) -> &[usize] {
         &self.cpu_ids
     }
+    /// Get `SCHED_DEADLINE` parameters
+    pub fn deadline(&self) -> Option<&DeadlineParams> {
+        self.deadline.as_ref()
+    }
+    /// Validates the parameters
+    pub fn validate(&self) -> Result<()> {
+        match (self.scheduling, self.deadline) {
+            (Scheduling::DeadLine, Some(deadline)) => {
+                if self.priority.is_some_and(|p| p != 0) {
+                    return Err(Error::InvalidData(
+                        "priority is not supported for SCHED_DEADLINE".to_owned(),
+                    ));
+                }
+                deadline.validate()
+            }
+            (Scheduling::DeadLine, None) => Err(Error::InvalidData(
+                "SCHED_DEADLINE requires runtime/deadline/period parameters".to_owned(),
+            )),
+            (_, Some(_)) => Err(Error::InvalidData(
+                "deadline parameters require SCHED_DEADLINE policy".to_owned(),
+            )),
+            (Scheduling::RoundRobin | Scheduling::FIFO, None) if self.priority.is_none() => Err(
+                Error::InvalidData("real-time scheduling policies require a priority".to_owned()),
+            ),
+            (_, None) => Ok(()),
+        }
+    }
+    // Batch and Idle policies accept priority 0 only, so it is implied if not set
+    fn effective_priority(&self) -> Option<i32> {
+        self.priority.or(match self.scheduling {
+            Scheduling::Batch | Scheduling::Idle => Some(0),
+            _ => None,
+        })
+    }
+    fn is_default(&self) -> bool {
+        self.priority.is_none()
+            && self.scheduling == Scheduling::Other
+            && self.cpu_ids.is_empty()
+            && self.deadline.is_none()
+    }
+}
+
+/// `SCHED_DEADLINE` parameters (Linux). The kernel guarantees the thread gets `runtime` of CPU
+/// time every `period`, before the `deadline` (relative to the period start)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DeadlineParams {
+    runtime: Duration,
+    deadline: Duration,
+    period: Duration,
+    reclaim: bool,
+    dl_overrun: bool,
+}
+
+impl DeadlineParams {
+    /// Creates new deadline parameters
+    pub fn new(runtime: Duration, deadline: Duration, period: Duration) -> Self {
+        Self {
+            runtime,
+            deadline,
+            period,
+            reclaim: false,
+            dl_overrun: false,
+        }
+    }
+    /// Allows the thread to reclaim the bandwidth, unused by other deadline threads
+    /// (`SCHED_FLAG_RECLAIM`)
+    pub fn with_reclaim(mut self, reclaim: bool) -> Self {
+        self.reclaim = reclaim;
+        self
+    }
+    /// Requests `SIGXCPU` to be sent to the thread on runtime overruns (`SCHED_FLAG_DL_OVERRUN`)
+    pub fn with_dl_overrun(mut self, dl_overrun: bool) -> Self {
+        self.dl_overrun = dl_overrun;
+        self
+    }
+    /// Get the runtime
+    pub fn runtime(&self) -> Duration {
+        self.runtime
+    }
+    /// Get the relative deadline
+    pub fn deadline(&self) -> Duration {
+        self.deadline
+    }
+    /// Get the period
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+    /// Get the reclaim flag
+    pub fn reclaim(&self) -> bool {
+        self.reclaim
+    }
+    /// Get the dl-overrun flag
+    pub fn dl_overrun(&self) -> bool {
+        self.dl_overrun
+    }
+    /// Validates the parameters: `1024ns <= runtime <= deadline <= period`, the values must fit
+    /// into 63 bits of nanoseconds
+    pub fn validate(&self) -> Result<()> {
+        const MIN_RUNTIME: Duration = Duration::from_nanos(1 << 10);
+        const MAX_VALUE: Duration = Duration::from_nanos(1 << 63);
+        if self.runtime < MIN_RUNTIME {
+            return Err(Error::InvalidData(format!(
+                "deadline runtime must be at least {:?}",
+                MIN_RUNTIME
+            )));
+        }
+        if self.runtime > self.deadline || self.deadline > self.period {
+            return Err(Error::InvalidData(
+                "deadline parameters must satisfy runtime <= deadline <= period".to_owned(),
+            ));
+        }
+        if self.period >= MAX_VALUE {
+            return Err(Error::InvalidData(
+                "deadline period is too large".to_owned(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// Scheduling policy (Linux)
@@ -75,24 +213,165 @@ pub enum Scheduling {
     Other,
 }
 
-/// Apply the thread scheduler and CPU affinity parameters for the current thread
+/// Apply the thread scheduler and CPU affinity parameters for the current thread. Default
+/// parameters are a no-op on all platforms
 #[inline]
 pub fn apply_for_current(params: &Params) -> Result<()> {
+    if params.is_default() {
+        return Ok(());
+    }
+    params.validate()?;
     os::apply_for_current(params)
 }
 
 /// Apply the thread scheduler and CPU affinity parameters for a given thread. heap preallocation
-/// is ignored
+/// is ignored. Default parameters are a no-op on all platforms
 #[inline]
 pub fn apply(tid: libc::c_int, params: &Params) -> Result<()> {
+    if params.is_default() {
+        return Ok(());
+    }
+    params.validate()?;
     os::apply(tid, params)
 }
 
+/// Get the thread scheduler, CPU affinity and deadline parameters of the current thread. For
+/// non-real-time policies the priority is reported as `Some(0)`
+#[inline]
+pub fn current() -> Result<Params> {
+    os::get(os::current_tid())
+}
+
+/// Get the thread scheduler, CPU affinity and deadline parameters of a given thread
+#[inline]
+pub fn get(tid: libc::c_int) -> Result<Params> {
+    os::get(tid)
+}
+
+/// Apply the parameters for the current thread. The previous settings, changed by the parameters,
+/// are restored when the returned guard object is dropped.
+///
+/// Example:
+///
+/// ```rust,no_run
+/// use rtsc::thread_rt::{self, Params, Scheduling};
+///
+/// {
+///     let _guard = thread_rt::apply_scoped(
+///         &Params::new().with_priority(Some(90)).with_scheduling(Scheduling::FIFO),
+///     )
+///     .expect("Unable to raise the priority");
+///     // critical section
+/// }
+/// // the previous priority is restored at the end of the scope
+/// ```
+pub fn apply_scoped(params: &Params) -> Result<ParamsGuard> {
+    if params.is_default() {
+        return Ok(ParamsGuard {
+            tid: 0,
+            restore: Params::default(),
+        });
+    }
+    params.validate()?;
+    let tid = os::current_tid();
+    let prev = os::get(tid)?;
+    let mut restore = Params::default();
+    if !params.cpu_ids.is_empty() {
+        restore.cpu_ids = prev.cpu_ids;
+    }
+    if params.effective_priority().is_some() || params.deadline.is_some() {
+        restore.priority = prev.priority;
+        restore.scheduling = prev.scheduling;
+        restore.deadline = prev.deadline;
+    }
+    let guard = ParamsGuard { tid, restore };
+    os::apply(tid, params)?;
+    Ok(guard)
+}
+
+/// A guard object to restore thread parameters when dropped
+#[must_use]
+pub struct ParamsGuard {
+    tid: libc::c_int,
+    restore: Params,
+}
+
+impl Drop for ParamsGuard {
+    fn drop(&mut self) {
+        if let Err(error) = apply(self.tid, &self.restore) {
+            tracing::warn!(tid = self.tid, %error, "Failed to restore thread parameters");
+        }
+    }
+}
+
+/// Get the real-time related permissions of the process: capabilities and resource limits
+#[inline]
+pub fn check_capabilities() -> Result<Capabilities> {
+    os::capabilities()
+}
+
+/// Real-time related permissions of the process (see [`check_capabilities`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    sys_nice: bool,
+    ipc_lock: bool,
+    rtprio_limit: u64,
+    memlock_limit: u64,
+}
+
+impl Capabilities {
+    /// `CAP_SYS_NICE` is effective
+    pub fn sys_nice(&self) -> bool {
+        self.sys_nice
+    }
+    /// `CAP_IPC_LOCK` is effective
+    pub fn ipc_lock(&self) -> bool {
+        self.ipc_lock
+    }
+    /// `RLIMIT_RTPRIO` soft limit, `u64::MAX` if unlimited
+    pub fn rtprio_limit(&self) -> u64 {
+        self.rtprio_limit
+    }
+    /// `RLIMIT_MEMLOCK` soft limit (in bytes), `u64::MAX` if unlimited
+    pub fn memlock_limit(&self) -> u64 {
+        self.memlock_limit
+    }
+    /// Checks if a real-time (FIFO/RoundRobin) priority can be set
+    pub fn can_set_rt_priority(&self, priority: i32) -> bool {
+        self.sys_nice || u64::try_from(priority).is_ok_and(|p| p <= self.rtprio_limit)
+    }
+    /// Checks if the deadline scheduling can be set (requires `CAP_SYS_NICE`)
+    pub fn can_set_deadline(&self) -> bool {
+        self.sys_nice
+    }
+    /// Checks if the given amount of memory can be locked
+    pub fn can_lock_memory(&self, size: usize) -> bool {
+        self.ipc_lock || u64::try_from(size).is_ok_and(|s| s <= self.memlock_limit)
+    }
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn check(&self, params: &Params) -> Result<()> {
+        if params.deadline.is_some() && !self.can_set_deadline() {
+            return Err(Error::AccessDenied);
+        }
+        if let Some(priority) = params.effective_priority() {
+            if priority > 0
+                && matches!(params.scheduling, Scheduling::RoundRobin | Scheduling::FIFO)
+                && !self.can_set_rt_priority(priority)
+            {
+                return Err(Error::AccessDenied);
+            }
+        }
+        Ok(())
+    }
+}
+
 /// The method preallocates a heap memory region with the given size. The method is useful to
 /// prevent memory fragmentation and speed up memory allocation. It is highly recommended to call
 /// the method at the beginning of the program.
 ///
-/// Does nothing in simulated mode.
+/// Does nothing in simulated mode. On non-glibc Linux (e.g. musl) the allocator can not be told
+/// to keep freed memory, so a locked region of the given size is reserved for the process lifetime
+/// instead and all memory, mapped in future, is locked.
 ///
 /// # Panics
 ///
@@ -101,3 +380,155 @@ pub fn apply(tid: libc::c_int, params: &Params) -> Result<()> {
 pub fn preallocate_heap(size: usize) -> Result<()> {
     os::prealloc_heap(size)
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{DeadlineParams, Params, Scheduling};
+
+    #[test]
+    fn test_builder() {
+        let handle = super::Builder::new()
+            .with_name("rt-worker")
+            .with_stack_size(256 * 1024)
+            .with_prefault_stack(64 * 1024)
+            .spawn(|| std::thread::current().name().map(ToOwned::to_owned))
+            .unwrap();
+        #[cfg(target_os = "linux")]
+        assert!(handle.tid() > 0);
+        assert_eq!(handle.join().unwrap().as_deref(), Some("rt-worker"));
+        let started = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let started_c = started.clone();
+        let result = super::Builder::new()
+            .with_params(
+                Params::new()
+                    .with_priority(Some(10))
+                    .with_scheduling(Scheduling::DeadLine),
+            )
+            .spawn(move || started_c.store(true, std::sync::atomic::Ordering::SeqCst));
+        assert!(result.is_err());
+        assert!(!started.load(std::sync::atomic::Ordering::SeqCst));
+        // the largest allowed prefault
+        super::Builder::new()
+            .with_stack_size(128 * 1024)
+            .with_prefault_stack(96 * 1024)
+            .spawn(|| ())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(super::Builder::new()
+            .with_stack_size(64 * 1024)
+            .with_prefault_stack(63 * 1024)
+            .spawn(|| ())
+            .is_err());
+        assert!(super::Builder::new()
+            .with_prefault_stack(64 * 1024)
+            .spawn(|| ())
+            .is_err());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let unprivileged = super::Capabilities {
+            rtprio_limit: 20,
+            memlock_limit: 65536,
+            ..Default::default()
+        };
+        assert!(unprivileged.can_set_rt_priority(20));
+        assert!(!unprivileged.can_set_rt_priority(21));
+        assert!(!unprivileged.can_set_deadline());
+        assert!(unprivileged.can_lock_memory(65536));
+        assert!(!unprivileged.can_lock_memory(65537));
+        let fifo = |priority| {
+            Params::new()
+                .with_priority(Some(priority))
+                .with_scheduling(Scheduling::FIFO)
+        };
+        assert!(unprivileged.check(&fifo(10)).is_ok());
+        assert!(unprivileged.check(&fifo(50)).is_err());
+        assert!(unprivileged
+            .check(&Params::new().with_priority(Some(0)))
+            .is_ok());
+        let deadline = Params::new().with_deadline(DeadlineParams::new(
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+            Duration::from_millis(10),
+        ));
+        assert!(unprivileged.check(&deadline).is_err());
+        let privileged = super::Capabilities {
+            sys_nice: true,
+            ipc_lock: true,
+            ..Default::default()
+        };
+        assert!(privileged.check(&fifo(99)).is_ok());
+        assert!(privileged.check(&deadline).is_ok());
+        assert!(privileged.can_lock_memory(usize::MAX));
+        #[cfg(target_os = "linux")]
+        super::check_capabilities().unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_current() {
+        let params = super::current().unwrap();
+        assert!(!params.cpu_ids().is_empty());
+        // switching to SCHED_BATCH requires no privileges
+        let guard = super::apply_scoped(&Params::new().with_scheduling(Scheduling::Batch)).unwrap();
+        let changed = super::current().unwrap();
+        assert_eq!(changed.scheduling(), Scheduling::Batch);
+        assert_eq!(changed.priority(), Some(0));
+        assert_eq!(changed.cpu_ids(), params.cpu_ids());
+        drop(guard);
+        let restored = super::current().unwrap();
+        assert_eq!(restored, params);
+        let scoped = Params::new()
+            .with_priority(Some(10))
+            .with_scheduling(Scheduling::FIFO);
+        // a real-time priority requires privileges
+        if let Ok(guard) = super::apply_scoped(&scoped) {
+            let raised = super::current().unwrap();
+            assert_eq!(raised.scheduling(), Scheduling::FIFO);
+            assert_eq!(raised.priority(), Some(10));
+            drop(guard);
+            assert_eq!(super::current().unwrap(), params);
+        }
+    }
+
+    #[test]
+    fn test_deadline_validate() {
+        let ms = Duration::from_millis;
+        let params = Params::new().with_deadline(DeadlineParams::new(ms(1), ms(5), ms(10)));
+        assert_eq!(params.scheduling(), Scheduling::DeadLine);
+        params.validate().unwrap();
+        for (runtime, deadline, period) in [
+            (Duration::from_nanos(100), ms(5), ms(10)),
+            (ms(6), ms(5), ms(10)),
+            (ms(1), ms(11), ms(10)),
+        ] {
+            assert!(Params::new()
+                .with_deadline(DeadlineParams::new(runtime, deadline, period))
+                .validate()
+                .is_err());
+        }
+        assert!(params
+            .clone()
+            .with_scheduling(Scheduling::FIFO)
+            .validate()
+            .is_err());
+        assert!(params.with_priority(Some(10)).validate().is_err());
+        assert!(Params::new()
+            .with_scheduling(Scheduling::DeadLine)
+            .with_priority(Some(10))
+            .validate()
+            .is_err());
+        // real-time policies require a priority, Batch/Idle imply 0
+        assert!(Params::new()
+            .with_scheduling(Scheduling::FIFO)
+            .validate()
+            .is_err());
+        let batch = Params::new().with_scheduling(Scheduling::Batch);
+        assert!(batch.validate().is_ok());
+        assert_eq!(batch.effective_priority(), Some(0));
+    }
+}
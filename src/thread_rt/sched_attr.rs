@@ -0,0 +1,133 @@
+use std::{io, time::Duration};
+
+use super::{DeadlineParams, Params, Scheduling};
+use crate::{Error, Result};
+
+const SCHED_FLAG_RECLAIM: u64 = 0x02;
+const SCHED_FLAG_DL_OVERRUN: u64 = 0x04;
+
+// the structure is defined locally as older libc versions do not provide it
+#[repr(C)]
+#[derive(Default)]
+struct SchedAttr {
+    size: u32,
+    sched_policy: u32,
+    sched_flags: u64,
+    sched_nice: i32,
+    sched_priority: u32,
+    sched_runtime: u64,
+    sched_deadline: u64,
+    sched_period: u64,
+}
+
+impl From<Scheduling> for libc::c_int {
+    fn from(value: Scheduling) -> Self {
+        match value {
+            Scheduling::RoundRobin => libc::SCHED_RR,
+            Scheduling::FIFO => libc::SCHED_FIFO,
+            Scheduling::Idle => libc::SCHED_IDLE,
+            Scheduling::Batch => libc::SCHED_BATCH,
+            Scheduling::DeadLine => libc::SCHED_DEADLINE,
+            Scheduling::Other => libc::SCHED_NORMAL,
+        }
+    }
+}
+
+impl TryFrom<libc::c_int> for Scheduling {
+    type Error = Error;
+
+    fn try_from(value: libc::c_int) -> Result<Self> {
+        match value {
+            libc::SCHED_RR => Ok(Scheduling::RoundRobin),
+            libc::SCHED_FIFO => Ok(Scheduling::FIFO),
+            libc::SCHED_IDLE => Ok(Scheduling::Idle),
+            libc::SCHED_BATCH => Ok(Scheduling::Batch),
+            libc::SCHED_DEADLINE => Ok(Scheduling::DeadLine),
+            libc::SCHED_NORMAL => Ok(Scheduling::Other),
+            v => Err(Error::InvalidData(format!(
+                "unsupported scheduling policy {}",
+                v
+            ))),
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn set_deadline(tid: libc::c_int, params: &DeadlineParams) -> Result<()> {
+    let mut flags = 0;
+    if params.reclaim {
+        flags |= SCHED_FLAG_RECLAIM;
+    }
+    if params.dl_overrun {
+        flags |= SCHED_FLAG_DL_OVERRUN;
+    }
+    let attr = SchedAttr {
+        size: std::mem::size_of::<SchedAttr>() as u32,
+        sched_policy: libc::SCHED_DEADLINE as u32,
+        sched_flags: flags,
+        sched_runtime: params.runtime.as_nanos() as u64,
+        sched_deadline: params.deadline.as_nanos() as u64,
+        sched_period: params.period.as_nanos() as u64,
+        ..SchedAttr::default()
+    };
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_sched_setattr,
+            tid,
+            &attr as *const SchedAttr,
+            0 as libc::c_uint,
+        )
+    };
+    if res != 0 {
+        return Err(Error::RTSchedSetScheduler(
+            io::Error::last_os_error().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the scheduling policy, priority, deadline attributes and CPU affinity of the thread
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn get(tid: libc::c_int) -> Result<Params> {
+    let mut attr = SchedAttr::default();
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_sched_getattr,
+            tid,
+            &mut attr as *mut SchedAttr,
+            std::mem::size_of::<SchedAttr>() as libc::c_uint,
+            0 as libc::c_uint,
+        )
+    };
+    if res != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    let scheduling = Scheduling::try_from(attr.sched_policy as libc::c_int)?;
+    let deadline = (scheduling == Scheduling::DeadLine).then(|| DeadlineParams {
+        runtime: Duration::from_nanos(attr.sched_runtime),
+        deadline: Duration::from_nanos(attr.sched_deadline),
+        period: Duration::from_nanos(attr.sched_period),
+        reclaim: attr.sched_flags & SCHED_FLAG_RECLAIM != 0,
+        dl_overrun: attr.sched_flags & SCHED_FLAG_DL_OVERRUN != 0,
+    });
+    let cpu_ids = unsafe {
+        let mut cpuset: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &mut cpuset) != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|cpu| libc::CPU_ISSET(*cpu, &cpuset))
+            .collect()
+    };
+    Ok(Params {
+        priority: Some(attr.sched_priority as i32),
+        scheduling,
+        cpu_ids,
+        deadline,
+    })
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub fn current_tid() -> libc::c_int {
+    unsafe { libc::syscall(libc::SYS_gettid) as libc::c_int }
+}
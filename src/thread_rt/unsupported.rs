@@ -1,4 +1,4 @@
-use super::Params;
+use super::{Capabilities, Params};
 use crate::{Error, Result};
 
 pub fn apply_for_current(_params: &Params) -> Result<()> {
@@ -9,6 +9,18 @@ pub fn apply(_tid: libc::c_int, _params: &Params) -> Result<()> {
     Err(Error::Unimplemented)
 }
 
+pub fn capabilities() -> Result<Capabilities> {
+    Err(Error::Unimplemented)
+}
+
+pub fn current_tid() -> libc::c_int {
+    0
+}
+
+pub fn get(_tid: libc::c_int) -> Result<Params> {
+    Err(Error::Unimplemented)
+}
+
 pub fn prealloc_heap(size: usize) -> Result<()> {
     if size == 0 {
         return Ok(());
@@ -1,4 +1,4 @@
-use std::{num::Wrapping, thread, time::Duration};
+use std::{num::Wrapping, sync::OnceLock, thread, time::Duration};
 
 use bma_ts::Monotonic;
 
@@ -31,6 +31,14 @@ impl DurationRT for Duration {
     }
 }
 
+static EPOCH: OnceLock<Monotonic> = OnceLock::new();
+
+/// Returns the process-wide time base (the monotonic time of the first call). Intervals, aligned
+/// to the same epoch (see [`Interval::set_phase`]), tick in deterministic relative phases
+pub fn epoch() -> Monotonic {
+    *EPOCH.get_or_init(Monotonic::now)
+}
+
 /// Creates a new [`Interval`]
 pub fn interval(period: Duration) -> Interval {
     Interval::new(period)
@@ -43,18 +51,24 @@ pub fn interval_hz(frequency: u64) -> Interval {
 
 /// A synchronous interval helper, similar to
 /// <https://docs.rs/tokio/latest/tokio/time/struct.Interval.html>
+///
+/// The iterator yields false if a tick is missed (see [`Tick::is_on_time`]).
 pub struct Interval {
     next_tick: Option<Monotonic>,
     period: Duration,
     missing_tick_behavior: MissedTickBehavior,
     ticks: Wrapping<usize>,
+    sleep_mode: SleepMode,
+    spin: Duration,
+    stats: IntervalStats,
+    alignment: Option<(Option<Monotonic>, Duration)>,
 }
 
 impl Iterator for Interval {
     type Item = bool;
 
     fn next(&mut self) -> Option<bool> {
-        Some(self.tick())
+        Some(self.tick().is_on_time())
     }
 }
 
@@ -66,24 +80,63 @@ impl Interval {
             period,
             missing_tick_behavior: <_>::default(),
             ticks: Wrapping(0),
+            sleep_mode: <_>::default(),
+            spin: Duration::ZERO,
+            stats: <_>::default(),
+            alignment: None,
         }
     }
     /// Ticks the interval
     ///
-    /// Returns false if a tick is missed
-    pub fn tick(&mut self) -> bool {
+    /// Returns the tick result with the measured wakeup jitter. The tick is not on time if missed
+    pub fn tick(&mut self) -> Tick {
+        let schedule = self.schedule(Monotonic::now());
+        if let Some(deadline) = schedule.wait_until {
+            sleep_until(deadline, self.sleep_mode, self.spin);
+            let jitter = Monotonic::now().abs_diff(deadline);
+            self.stats.record_latency(jitter);
+            Tick {
+                on_time: true,
+                jitter,
+            }
+        } else if let Some(missed_by) = schedule.missed_by {
+            self.stats.record_overrun(missed_by);
+            Tick {
+                on_time: false,
+                jitter: missed_by,
+            }
+        } else {
+            Tick {
+                on_time: true,
+                jitter: Duration::ZERO,
+            }
+        }
+    }
+    /// Returns a snapshot of the cycle statistics. The statistics are collected by
+    /// [`Interval::tick`]
+    pub fn stats(&self) -> IntervalStats {
+        self.stats
+    }
+    /// Resets the cycle statistics
+    pub fn reset_stats(&mut self) {
+        self.stats = <_>::default();
+    }
+    /// Schedules the next tick
+    pub(crate) fn schedule(&mut self, now: Monotonic) -> Schedule {
         self.ticks += Wrapping(1);
-        let now = Monotonic::now();
+        let mut schedule = Schedule {
+            wait_until: None,
+            missed_by: None,
+        };
         if let Some(mut next_tick) = self.next_tick {
             match now.cmp(&next_tick) {
                 std::cmp::Ordering::Less => {
-                    let to_sleep = next_tick - now;
                     self.next_tick = Some(next_tick + self.period);
-                    thread::sleep(to_sleep);
-                    true
+                    schedule.wait_until = Some(next_tick);
                 }
-                std::cmp::Ordering::Equal => true,
+                std::cmp::Ordering::Equal => {}
                 std::cmp::Ordering::Greater => {
+                    schedule.missed_by = Some(now - next_tick);
                     match self.missing_tick_behavior {
                         MissedTickBehavior::Burst => {
                             self.next_tick = Some(next_tick + self.period);
@@ -98,13 +151,29 @@ impl Interval {
                             self.next_tick = Some(next_tick);
                         }
                     }
-                    false
                 }
             }
+        } else if let Some((epoch, phase)) = self.alignment {
+            let first_tick = self.aligned_tick(epoch.unwrap_or_else(self::epoch) + phase, now);
+            self.next_tick = Some(first_tick + self.period);
+            if first_tick > now {
+                schedule.wait_until = Some(first_tick);
+            }
         } else {
             self.next_tick = Some(now + self.period);
-            true
         }
+        schedule
+    }
+    /// Returns the first tick of the schedule, started at the origin, which is not earlier than
+    /// now
+    #[allow(clippy::cast_possible_truncation)]
+    fn aligned_tick(&self, origin: Monotonic, now: Monotonic) -> Monotonic {
+        if now <= origin {
+            return origin;
+        }
+        let elapsed = (now - origin).as_nanos();
+        let period = self.period.as_nanos().max(1);
+        origin + Duration::from_nanos((elapsed.div_ceil(period) * period) as u64)
     }
     /// Returns the number of ticks elapsed. If a tick is skipped, the counter is not incremented.
     /// In case if the tick counter reaches `usize::MAX`, it is reset to zero
@@ -117,12 +186,208 @@ impl Interval {
         self
     }
 
+    /// Aligns the interval schedule to the process-wide [`epoch`] with the given phase offset:
+    /// ticks happen at `epoch + phase + N * period`. The first tick waits for the nearest aligned
+    /// point of time. Can be used as a build pattern
+    pub fn set_phase(mut self, phase: Duration) -> Self {
+        let epoch = self.alignment.and_then(|(epoch, _)| epoch);
+        self.alignment = Some((epoch, phase));
+        self
+    }
+    /// Aligns the interval schedule to a custom epoch (see [`Interval::set_phase`]). Can be used
+    /// as a build pattern
+    pub fn set_epoch(mut self, epoch: Monotonic) -> Self {
+        let phase = self.alignment.map(|(_, phase)| phase).unwrap_or_default();
+        self.alignment = Some((Some(epoch), phase));
+        self
+    }
+    /// Sets the sleep mode. Can be used as a build pattern
+    pub fn set_sleep_mode(mut self, sleep_mode: SleepMode) -> Self {
+        self.sleep_mode = sleep_mode;
+        self
+    }
+    /// Sets the length of the final busy-wait phase. The thread sleeps until the deadline minus
+    /// the spin duration and then spins until the deadline. Can be used as a build pattern
+    pub fn set_spin(mut self, spin: Duration) -> Self {
+        self.spin = spin;
+        self
+    }
+
     /// Returns the period of the interval.
     pub fn period(&self) -> Duration {
         self.period
     }
 }
 
+/// Interval tick result
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Tick {
+    on_time: bool,
+    jitter: Duration,
+}
+
+impl Tick {
+    /// Returns false if the tick is missed
+    pub fn is_on_time(&self) -> bool {
+        self.on_time
+    }
+    /// The wakeup jitter: the difference between the actual wakeup time and the tick deadline.
+    /// For missed ticks, the time passed since the missed deadline
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+}
+
+/// The number of the interval jitter histogram buckets
+pub const JITTER_HISTOGRAM_BUCKETS: usize = 16;
+
+/// Interval cycle statistics
+///
+/// The wakeup latency is the difference between the actual wakeup time and the tick deadline.
+/// Latencies are collected into a histogram with power-of-two microsecond buckets: the bucket `0`
+/// contains latencies below 1us, the bucket `N` - latencies below `2^N` us, the last bucket
+/// contains all the higher ones (see [`IntervalStats::bucket_limit`])
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct IntervalStats {
+    samples: u64,
+    min_latency: Duration,
+    max_latency: Duration,
+    total_latency: Duration,
+    histogram: [u64; JITTER_HISTOGRAM_BUCKETS],
+    overruns: u64,
+    longest_overrun: Duration,
+}
+
+impl IntervalStats {
+    fn record_latency(&mut self, latency: Duration) {
+        if self.samples == 0 || latency < self.min_latency {
+            self.min_latency = latency;
+        }
+        if latency > self.max_latency {
+            self.max_latency = latency;
+        }
+        self.samples += 1;
+        self.total_latency = self.total_latency.saturating_add(latency);
+        let micros = latency.as_micros();
+        let bucket = if micros == 0 {
+            0
+        } else {
+            usize::try_from(micros.ilog2() + 1).unwrap_or(usize::MAX)
+        };
+        self.histogram[bucket.min(JITTER_HISTOGRAM_BUCKETS - 1)] += 1;
+    }
+    fn record_overrun(&mut self, overrun: Duration) {
+        self.overruns += 1;
+        if overrun > self.longest_overrun {
+            self.longest_overrun = overrun;
+        }
+    }
+    /// The number of measured wakeups (on-time ticks)
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+    /// Minimal wakeup latency, None if there are no samples
+    pub fn min_latency(&self) -> Option<Duration> {
+        (self.samples > 0).then_some(self.min_latency)
+    }
+    /// Maximal wakeup latency, None if there are no samples
+    pub fn max_latency(&self) -> Option<Duration> {
+        (self.samples > 0).then_some(self.max_latency)
+    }
+    /// Mean wakeup latency, None if there are no samples
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn mean_latency(&self) -> Option<Duration> {
+        (self.samples > 0).then(|| {
+            Duration::from_nanos((self.total_latency.as_nanos() / u128::from(self.samples)) as u64)
+        })
+    }
+    /// Wakeup latency histogram
+    pub fn histogram(&self) -> &[u64; JITTER_HISTOGRAM_BUCKETS] {
+        &self.histogram
+    }
+    /// Returns the exclusive upper limit of the histogram bucket, None for the last one
+    pub fn bucket_limit(bucket: usize) -> Option<Duration> {
+        (bucket < JITTER_HISTOGRAM_BUCKETS - 1).then(|| Duration::from_micros(1 << bucket))
+    }
+    /// The number of overruns (missed ticks)
+    pub fn overruns(&self) -> u64 {
+        self.overruns
+    }
+    /// The longest overrun: the time passed since the missed tick deadline
+    pub fn longest_overrun(&self) -> Duration {
+        self.longest_overrun
+    }
+}
+
+pub(crate) struct Schedule {
+    /// the deadline to wait until
+    pub(crate) wait_until: Option<Monotonic>,
+    /// the tick is missed
+    pub(crate) missed_by: Option<Duration>,
+}
+
+/// Interval sleep mode
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SleepMode {
+    #[default]
+    /// Relative sleeps with [`thread::sleep`]
+    Relative,
+    /// Sleeps to absolute `CLOCK_MONOTONIC` deadlines with `clock_nanosleep(TIMER_ABSTIME)`, so
+    /// wakeups do not drift with scheduler latency (Linux only, other platforms fall back to
+    /// relative sleeps)
+    Absolute,
+}
+
+fn sleep_until(deadline: Monotonic, sleep_mode: SleepMode, spin: Duration) {
+    if deadline.as_duration() > spin {
+        let wake_at = deadline - spin;
+        match sleep_mode {
+            SleepMode::Relative => {
+                let now = Monotonic::now();
+                if wake_at > now {
+                    thread::sleep(wake_at - now);
+                }
+            }
+            SleepMode::Absolute => sleep_absolute(wake_at),
+        }
+    }
+    while Monotonic::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[allow(clippy::cast_possible_wrap)]
+fn sleep_absolute(deadline: Monotonic) {
+    let t = deadline.as_duration();
+    let ts = libc::timespec {
+        tv_sec: t.as_secs() as libc::time_t,
+        tv_nsec: libc::c_long::from(t.subsec_nanos()),
+    };
+    loop {
+        // the call returns errors directly instead of setting errno
+        let res = unsafe {
+            libc::clock_nanosleep(
+                libc::CLOCK_MONOTONIC,
+                libc::TIMER_ABSTIME,
+                &ts,
+                std::ptr::null_mut(),
+            )
+        };
+        if res != libc::EINTR {
+            break;
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn sleep_absolute(deadline: Monotonic) {
+    let now = Monotonic::now();
+    if deadline > now {
+        thread::sleep(deadline - now);
+    }
+}
+
 /// Interval missing tick behavior
 ///
 /// The behavior is similar to
@@ -167,4 +432,71 @@ mod test {
         }
         assert_eq!(int.elapsed_ticks(), 3);
     }
+
+    #[test]
+    fn test_tick_jitter() {
+        let mut int = super::interval(Duration::from_millis(5))
+            .set_sleep_mode(super::SleepMode::Absolute)
+            .set_spin(Duration::from_micros(200));
+        let started = Monotonic::now();
+        for _ in 0..10 {
+            let tick = int.tick();
+            if tick.is_on_time() {
+                assert!(tick.jitter() < Duration::from_millis(5));
+            }
+        }
+        // the first tick is instant
+        assert!(started.elapsed() >= Duration::from_millis(45));
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut int = super::interval(Duration::from_millis(5));
+        for _ in 0..5 {
+            int.tick();
+        }
+        let stats = int.stats();
+        assert_eq!(stats.samples(), 4);
+        assert_eq!(stats.histogram().iter().sum::<u64>(), 4);
+        assert!(stats.min_latency().unwrap() <= stats.mean_latency().unwrap());
+        assert!(stats.mean_latency().unwrap() <= stats.max_latency().unwrap());
+        assert_eq!(stats.overruns(), 0);
+        thread::sleep(Duration::from_millis(20));
+        assert!(!int.tick().is_on_time());
+        let stats = int.stats();
+        assert_eq!(stats.overruns(), 1);
+        assert!(stats.longest_overrun() >= Duration::from_millis(10));
+        int.reset_stats();
+        let stats = int.stats();
+        assert_eq!(stats.samples(), 0);
+        assert_eq!(stats.overruns(), 0);
+        assert!(stats.min_latency().is_none());
+        assert_eq!(
+            super::IntervalStats::bucket_limit(3),
+            Some(Duration::from_micros(8))
+        );
+        assert!(super::IntervalStats::bucket_limit(super::JITTER_HISTOGRAM_BUCKETS - 1).is_none());
+    }
+
+    #[test]
+    fn test_phase() {
+        let epoch = Monotonic::now();
+        let mut int = super::interval(Duration::from_millis(10))
+            .set_epoch(epoch)
+            .set_phase(Duration::from_millis(3));
+        let schedule = int.schedule(epoch + Duration::from_millis(25));
+        assert_eq!(schedule.wait_until, Some(epoch + Duration::from_millis(33)));
+        let schedule = int.schedule(epoch + Duration::from_millis(34));
+        assert_eq!(schedule.wait_until, Some(epoch + Duration::from_millis(43)));
+        let mut int = super::interval(Duration::from_millis(10))
+            .set_epoch(epoch)
+            .set_phase(Duration::from_millis(3));
+        let schedule = int.schedule(epoch + Duration::from_millis(1));
+        assert_eq!(schedule.wait_until, Some(epoch + Duration::from_millis(3)));
+        // process-wide epoch
+        let mut int = super::interval(Duration::from_millis(5)).set_phase(Duration::from_millis(1));
+        let epoch = super::epoch();
+        let schedule = int.schedule(epoch + Duration::from_millis(7));
+        assert_eq!(schedule.wait_until, Some(epoch + Duration::from_millis(11)));
+    }
 }
@@ -0,0 +1,342 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+use bma_ts::Monotonic;
+use pin_project::pin_project;
+
+use crate::{
+    condvar_api::RawCondvar,
+    locking::{Condvar, RawMutex},
+    oneshot, thread_rt,
+    time::{self, MissedTickBehavior},
+    Error, Result,
+};
+
+const THREAD_NAME: &str = "rtsc-timer";
+
+static TIMER: OnceLock<Timer> = OnceLock::new();
+
+type TimerKey = (Monotonic, usize);
+
+struct TimerState {
+    entries: BTreeMap<TimerKey, Waker>,
+    next_id: usize,
+}
+
+struct TimerInner {
+    state: lock_api::Mutex<RawMutex, TimerState>,
+    cv: Condvar,
+}
+
+impl TimerInner {
+    fn run(&self) {
+        let mut state = self.state.lock();
+        loop {
+            let now = Monotonic::now();
+            let mut expired = state.entries.split_off(&(now, usize::MAX));
+            mem::swap(&mut expired, &mut state.entries);
+            if !expired.is_empty() {
+                drop(state);
+                for waker in expired.into_values() {
+                    waker.wake();
+                }
+                state = self.state.lock();
+                continue;
+            }
+            if let Some(((deadline, _), _)) = state.entries.first_key_value() {
+                let to_wait = *deadline - now;
+                RawCondvar::wait_for::<TimerState, RawMutex>(&self.cv, &mut state, to_wait);
+            } else {
+                RawCondvar::wait::<TimerState, RawMutex>(&self.cv, &mut state);
+            }
+        }
+    }
+}
+
+/// The shared timer. Serves all the timer futures of the process in a single thread
+struct Timer {
+    inner: Arc<TimerInner>,
+}
+
+impl Timer {
+    fn spawn(params: &thread_rt::Params) -> (Self, Result<()>) {
+        let inner = Arc::new(TimerInner {
+            state: lock_api::Mutex::new(TimerState {
+                entries: BTreeMap::new(),
+                next_id: 0,
+            }),
+            cv: Condvar::new(),
+        });
+        let (tx, rx) = oneshot::channel::<Result<()>, RawMutex, Condvar>();
+        let thread_inner = inner.clone();
+        let params = params.clone();
+        let spawned = thread::Builder::new()
+            .name(THREAD_NAME.to_owned())
+            .spawn(move || {
+                let _ = tx.send(thread_rt::apply_for_current(&params));
+                thread_inner.run();
+            });
+        let result = match spawned {
+            Ok(_) => rx.recv().and_then(|r| r),
+            Err(e) => Err(e.into()),
+        };
+        (Self { inner }, result)
+    }
+    fn get() -> &'static Self {
+        TIMER.get_or_init(|| {
+            let (timer, result) = Self::spawn(&thread_rt::Params::default());
+            if let Err(error) = result {
+                tracing::error!(%error, "unable to start the timer thread");
+            }
+            timer
+        })
+    }
+    fn register(&self, deadline: Monotonic, waker: &Waker) -> TimerKey {
+        let mut state = self.inner.state.lock();
+        let key = (deadline, state.next_id);
+        state.next_id = state.next_id.wrapping_add(1);
+        state.entries.insert(key, waker.clone());
+        if state
+            .entries
+            .first_key_value()
+            .is_some_and(|(k, _)| *k == key)
+        {
+            self.inner.cv.notify_one();
+        }
+        key
+    }
+    fn update(&self, key: TimerKey, waker: &Waker) {
+        if let Some(w) = self.inner.state.lock().entries.get_mut(&key) {
+            w.clone_from(waker);
+        }
+    }
+    fn unregister(&self, key: TimerKey) {
+        self.inner.state.lock().entries.remove(&key);
+    }
+}
+
+/// Starts the shared timer thread with the given scheduling parameters. If not called, the
+/// thread is started with the default parameters on the first timer use.
+///
+/// Returns an error if the thread is already running or the parameters can not be applied (in
+/// the latter case the thread keeps running with the default scheduling)
+pub fn start(params: &thread_rt::Params) -> Result<()> {
+    let mut result = Err(Error::Failed(
+        "the timer thread is already running".to_owned(),
+    ));
+    TIMER.get_or_init(|| {
+        let (timer, res) = Timer::spawn(params);
+        result = res;
+        timer
+    });
+    result
+}
+
+/// A future which completes at the given deadline. Does not depend on any async runtime
+pub struct Sleep {
+    deadline: Monotonic,
+    key: Option<TimerKey>,
+}
+
+impl Sleep {
+    /// The sleep deadline
+    pub fn deadline(&self) -> Monotonic {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Monotonic::now() >= self.deadline {
+            if let Some(key) = self.key.take() {
+                Timer::get().unregister(key);
+            }
+            return Poll::Ready(());
+        }
+        let timer = Timer::get();
+        if let Some(key) = self.key {
+            timer.update(key, cx.waker());
+        } else {
+            self.key = Some(timer.register(self.deadline, cx.waker()));
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            Timer::get().unregister(key);
+        }
+    }
+}
+
+/// Creates a future which completes after the given duration
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(Monotonic::now() + duration)
+}
+
+/// Creates a future which completes at the given monotonic time
+pub fn sleep_until(deadline: Monotonic) -> Sleep {
+    Sleep {
+        deadline,
+        key: None,
+    }
+}
+
+/// A future which limits the execution time of the inner one. Does not depend on any async
+/// runtime. If the time is out, the inner future is dropped (cancelled) with the timeout future
+#[pin_project]
+pub struct Timeout<F> {
+    #[pin]
+    future: F,
+    sleep: Sleep,
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(v) = this.future.poll(cx) {
+            return Poll::Ready(Ok(v));
+        }
+        Pin::new(this.sleep).poll(cx).map(|()| Err(Error::Timeout))
+    }
+}
+
+/// Limits the execution time of the future. Returns [`Error::Timeout`] if the future is not
+/// completed in time
+pub fn timeout<F: Future>(duration: Duration, future: F) -> Timeout<F> {
+    Timeout {
+        future,
+        sleep: sleep(duration),
+    }
+}
+
+/// Creates a new async [`Interval`]
+pub fn interval(period: Duration) -> Interval {
+    Interval::new(period)
+}
+
+/// Creates a new async [`Interval`] with the specified frequency
+pub fn interval_hz(frequency: u64) -> Interval {
+    Interval::new(Duration::from_nanos(1_000_000_000 / frequency))
+}
+
+/// An asynchronous interval helper, has got the same semantics as [`time::Interval`] but does not
+/// block the thread and does not depend on any async runtime
+pub struct Interval {
+    inner: time::Interval,
+}
+
+impl From<time::Interval> for Interval {
+    fn from(inner: time::Interval) -> Self {
+        Self { inner }
+    }
+}
+
+impl Interval {
+    /// Creates a new interval helper with the specified period
+    pub fn new(period: Duration) -> Self {
+        time::Interval::new(period).into()
+    }
+    /// Ticks the interval
+    ///
+    /// Returns false if a tick is missed. The tick is accounted even if the future is dropped
+    /// before completion
+    pub fn tick(&mut self) -> impl Future<Output = bool> {
+        let schedule = self.inner.schedule(Monotonic::now());
+        let sleep = schedule.wait_until.map(sleep_until);
+        async move {
+            if let Some(sleep) = sleep {
+                sleep.await;
+            }
+            schedule.missed_by.is_none()
+        }
+    }
+    /// Returns the number of ticks elapsed (see [`time::Interval::elapsed_ticks`])
+    pub fn elapsed_ticks(&self) -> usize {
+        self.inner.elapsed_ticks()
+    }
+    /// Sets missing tick behavior policy. Can be used as a build pattern
+    pub fn set_missing_tick_behavior(mut self, missing_tick_behavior: MissedTickBehavior) -> Self {
+        self.inner = self.inner.set_missing_tick_behavior(missing_tick_behavior);
+        self
+    }
+    /// Returns the period of the interval.
+    pub fn period(&self) -> Duration {
+        self.inner.period()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bma_ts::Monotonic;
+
+    use super::{interval, sleep, timeout, Timer};
+    use crate::{time::MissedTickBehavior, Error};
+
+    #[tokio::test]
+    async fn test_sleep() {
+        let started = Monotonic::now();
+        sleep(Duration::from_millis(20)).await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        assert_eq!(
+            timeout(Duration::from_millis(100), async { 42 })
+                .await
+                .unwrap(),
+            42
+        );
+        let started = Monotonic::now();
+        assert!(matches!(
+            timeout(Duration::from_millis(20), std::future::pending::<()>()).await,
+            Err(Error::Timeout)
+        ));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        let long = timeout(Duration::from_secs(3600), std::future::pending::<()>());
+        let res = timeout(Duration::from_millis(10), long).await;
+        assert!(matches!(res, Err(Error::Timeout)));
+        // the cancelled timers are unregistered
+        let state = Timer::get().inner.state.lock();
+        assert!(state
+            .entries
+            .keys()
+            .all(|(deadline, _)| *deadline < Monotonic::now() + Duration::from_secs(60)));
+    }
+
+    #[tokio::test]
+    async fn test_interval() {
+        let mut int = interval(Duration::from_millis(10));
+        let started = Monotonic::now();
+        for _ in 0..4 {
+            assert!(int.tick().await);
+        }
+        assert!(started.elapsed() >= Duration::from_millis(30));
+        assert_eq!(int.elapsed_ticks(), 4);
+        std::thread::sleep(Duration::from_millis(35));
+        // the missed ticks are fired instantly
+        assert!(!int.tick().await);
+        let mut int =
+            interval(Duration::from_millis(10)).set_missing_tick_behavior(MissedTickBehavior::Skip);
+        int.tick().await;
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(!int.tick().await);
+        assert!(int.tick().await);
+        assert_eq!(int.elapsed_ticks(), 3);
+    }
+}
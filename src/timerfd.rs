@@ -0,0 +1,229 @@
+use std::{
+    io,
+    num::Wrapping,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    time::Duration,
+};
+
+use crate::{time::MissedTickBehavior, Error, Result};
+
+/// A periodic timer, based on Linux `timerfd`. Unlike [`crate::time::Interval`], the timer is a
+/// waitable source: its file descriptor can be used in `poll`/`epoll`-based multi-source waits,
+/// e.g. to wait for the next tick or an incoming event on a socket/eventfd. Once the descriptor is
+/// readable, call [`PeriodicTimer::try_wait`] to get the expirations.
+///
+/// The timer uses the absolute `CLOCK_MONOTONIC` schedule, so ticks do not drift with the
+/// computation time.
+pub struct PeriodicTimer {
+    fd: OwnedFd,
+    period: Duration,
+    missing_tick_behavior: MissedTickBehavior,
+    backlog: u64,
+    ticks: Wrapping<usize>,
+}
+
+impl PeriodicTimer {
+    /// Creates a new timer with the specified period. The first tick happens after the period
+    pub fn new(period: Duration) -> Result<Self> {
+        if period.is_zero() {
+            return Err(Error::InvalidData("the period must be non-zero".to_owned()));
+        }
+        let fd = unsafe {
+            libc::timerfd_create(
+                libc::CLOCK_MONOTONIC,
+                libc::TFD_CLOEXEC | libc::TFD_NONBLOCK,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let timer = Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            period,
+            missing_tick_behavior: <_>::default(),
+            backlog: 0,
+            ticks: Wrapping(0),
+        };
+        timer.arm()?;
+        Ok(timer)
+    }
+    /// Sets missing tick behavior policy. Can be used as a build pattern
+    ///
+    /// * [`MissedTickBehavior::Burst`]: the missed ticks are returned one by one instantly by the
+    ///   following waits
+    ///
+    /// * [`MissedTickBehavior::Delay`]: the timer is restarted from the current point of time
+    ///
+    /// * [`MissedTickBehavior::Skip`]: the missed ticks are reported in the number of expirations
+    ///   only, the timer keeps the original schedule
+    pub fn set_missing_tick_behavior(mut self, missing_tick_behavior: MissedTickBehavior) -> Self {
+        self.missing_tick_behavior = missing_tick_behavior;
+        self
+    }
+    /// Waits for the next tick (blocking). Returns the number of the timer expirations since the
+    /// last read (more than 1 if ticks have been missed, always 1 for
+    /// [`MissedTickBehavior::Burst`])
+    pub fn wait(&mut self) -> Result<u64> {
+        self.wait_for(None)
+    }
+    /// Waits for the next tick with the given timeout
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Result<u64> {
+        self.wait_for(Some(timeout))
+    }
+    /// Checks the timer (non-blocking). Returns the number of the timer expirations since the last
+    /// read, zero if the timer has not been expired
+    pub fn try_wait(&mut self) -> Result<u64> {
+        if self.backlog > 0 {
+            self.backlog -= 1;
+            self.ticks += Wrapping(1);
+            return Ok(1);
+        }
+        let mut buf = [0u8; 8];
+        let res = unsafe { libc::read(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+        if res < 0 {
+            let e = io::Error::last_os_error();
+            return if e.kind() == io::ErrorKind::WouldBlock {
+                Ok(0)
+            } else {
+                Err(e.into())
+            };
+        }
+        let expirations = u64::from_ne_bytes(buf);
+        if expirations > 1 {
+            match self.missing_tick_behavior {
+                MissedTickBehavior::Burst => {
+                    self.backlog = expirations - 1;
+                    self.ticks += Wrapping(1);
+                    return Ok(1);
+                }
+                MissedTickBehavior::Delay => self.arm()?,
+                MissedTickBehavior::Skip => {}
+            }
+        }
+        self.ticks += Wrapping(1);
+        Ok(expirations)
+    }
+    fn wait_for(&mut self, timeout: Option<Duration>) -> Result<u64> {
+        let op = timeout.map(crate::ops::Operation::new);
+        loop {
+            let expirations = self.try_wait()?;
+            if expirations > 0 {
+                return Ok(expirations);
+            }
+            let remaining = op
+                .as_ref()
+                .map(crate::ops::Operation::remaining)
+                .transpose()?;
+            self.poll(remaining)?;
+        }
+    }
+    #[allow(clippy::cast_possible_wrap)]
+    fn poll(&self, timeout: Option<Duration>) -> Result<()> {
+        let mut pfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ts = timeout.map(|t| libc::timespec {
+            tv_sec: t.as_secs() as libc::time_t,
+            tv_nsec: libc::c_long::from(t.subsec_nanos()),
+        });
+        let res = unsafe {
+            libc::ppoll(
+                &mut pfd,
+                1,
+                ts.as_ref().map_or(std::ptr::null(), |t| t as *const _),
+                std::ptr::null(),
+            )
+        };
+        if res < 0 {
+            let e = io::Error::last_os_error();
+            if e.kind() != io::ErrorKind::Interrupted {
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+    #[allow(clippy::cast_possible_wrap)]
+    fn arm(&self) -> Result<()> {
+        let period = libc::timespec {
+            tv_sec: self.period.as_secs() as libc::time_t,
+            tv_nsec: libc::c_long::from(self.period.subsec_nanos()),
+        };
+        let spec = libc::itimerspec {
+            it_interval: period,
+            it_value: period,
+        };
+        let res =
+            unsafe { libc::timerfd_settime(self.fd.as_raw_fd(), 0, &spec, std::ptr::null_mut()) };
+        if res < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+    /// Returns the number of ticks elapsed. In case if the tick counter reaches `usize::MAX`, it
+    /// is reset to zero
+    pub fn elapsed_ticks(&self) -> usize {
+        self.ticks.0
+    }
+    /// Returns the period of the timer
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+impl AsFd for PeriodicTimer {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for PeriodicTimer {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use bma_ts::Monotonic;
+
+    use super::PeriodicTimer;
+    use crate::{time::MissedTickBehavior, Error};
+
+    #[test]
+    fn test_periodic_timer() {
+        let mut timer = PeriodicTimer::new(Duration::from_millis(10)).unwrap();
+        assert_eq!(timer.try_wait().unwrap(), 0);
+        let started = Monotonic::now();
+        for _ in 0..3 {
+            assert_eq!(timer.wait().unwrap(), 1);
+        }
+        assert!(started.elapsed() >= Duration::from_millis(25));
+        assert_eq!(timer.elapsed_ticks(), 3);
+        assert!(matches!(
+            timer.wait_timeout(Duration::from_millis(1)),
+            Err(Error::Timeout)
+        ));
+        assert!(PeriodicTimer::new(Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_periodic_timer_missed() {
+        let mut timer = PeriodicTimer::new(Duration::from_millis(10))
+            .unwrap()
+            .set_missing_tick_behavior(MissedTickBehavior::Skip);
+        thread::sleep(Duration::from_millis(35));
+        assert!(timer.wait().unwrap() >= 3);
+        let mut timer = PeriodicTimer::new(Duration::from_millis(10)).unwrap();
+        thread::sleep(Duration::from_millis(35));
+        let started = Monotonic::now();
+        for _ in 0..3 {
+            assert_eq!(timer.wait().unwrap(), 1);
+        }
+        // the missed ticks are returned instantly
+        assert!(started.elapsed() < Duration::from_millis(10));
+    }
+}
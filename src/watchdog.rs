@@ -0,0 +1,348 @@
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    thread,
+    time::Duration,
+};
+
+use bma_ts::Monotonic;
+
+use crate::{
+    cell::{Coupler, DataCell, TripleCoupler},
+    condvar_api::RawCondvar,
+    locking::RawMutex,
+    ops::Operation,
+    thread_rt, time, Error, Result,
+};
+use lock_api::RawMutex as RawMutexTrait;
+
+type StallHandler = Box<dyn FnMut(&Stall) + Send + 'static>;
+
+/// Components, which can be closed by a [`Watchdog`] when a task stalls
+pub trait Closeable {
+    /// Closes the component
+    fn close(&self);
+}
+
+impl<P, M, CV> Closeable for DataCell<P, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn close(&self) {
+        self.close();
+    }
+}
+
+impl<P, S, M, CV> Closeable for Coupler<P, S, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn close(&self) {
+        self.close();
+    }
+}
+
+impl<P, S, T, M, CV> Closeable for TripleCoupler<P, S, T, M, CV>
+where
+    M: RawMutexTrait,
+    CV: RawCondvar + RawCondvar<RawMutex = M>,
+{
+    fn close(&self) {
+        self.close();
+    }
+}
+
+/// A stalled task report
+#[derive(Debug, Clone)]
+pub struct Stall {
+    task: Arc<str>,
+    timeout: Duration,
+    overdue: Duration,
+}
+
+impl Stall {
+    /// The stalled task name
+    pub fn task(&self) -> &str {
+        &self.task
+    }
+    /// The task kick timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+    /// How much the kick is overdue (the time passed since the last kick minus the timeout)
+    pub fn overdue(&self) -> Duration {
+        self.overdue
+    }
+}
+
+struct TaskSlot {
+    name: Arc<str>,
+    timeout: Duration,
+    // monotonic nanoseconds
+    last_kick: AtomicU64,
+    stalled: AtomicBool,
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn monotonic_nanos() -> u64 {
+    Monotonic::now().as_nanos() as u64
+}
+
+struct WatchdogInner {
+    tasks: lock_api::Mutex<RawMutex, Vec<Arc<TaskSlot>>>,
+    handlers: lock_api::Mutex<RawMutex, Vec<StallHandler>>,
+    // serializes handler calls, the handlers are called with the list unlocked
+    dispatch: lock_api::Mutex<RawMutex, ()>,
+}
+
+/// A watchdog for real-time tasks. Each task registers itself and calls [`WatchdogHandle::kick`]
+/// every cycle. If a kick is overdue, the watchdog reports the stall to the registered handlers
+/// (once per stall). The watchdog can be checked either manually with [`Watchdog::check`] (e.g.
+/// inside a [`time::Interval`] loop) or by a monitor thread (see [`Watchdog::spawn`])
+#[derive(Clone)]
+pub struct Watchdog {
+    inner: Arc<WatchdogInner>,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Watchdog {
+    /// Creates a new watchdog
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(WatchdogInner {
+                tasks: lock_api::Mutex::new(Vec::new()),
+                handlers: lock_api::Mutex::new(Vec::new()),
+                dispatch: lock_api::Mutex::new(()),
+            }),
+        }
+    }
+    /// Registers a task with the given kick timeout. The task is considered as kicked at the
+    /// moment of registration. The task is unregistered when the handle is dropped
+    pub fn register(&self, name: &str, timeout: Duration) -> WatchdogHandle {
+        let slot = Arc::new(TaskSlot {
+            name: name.into(),
+            timeout,
+            last_kick: AtomicU64::new(monotonic_nanos()),
+            stalled: AtomicBool::new(false),
+        });
+        self.inner.tasks.lock().push(slot.clone());
+        WatchdogHandle {
+            slot,
+            watchdog: Arc::downgrade(&self.inner),
+        }
+    }
+    /// Registers a stall handler. Handlers may register other handlers but must not call
+    /// [`Watchdog::check`]
+    pub fn on_stall<F>(&self, f: F)
+    where
+        F: FnMut(&Stall) + Send + 'static,
+    {
+        self.inner.handlers.lock().push(Box::new(f));
+    }
+    /// Closes the component (e.g. a [`DataCell`]) on the first stall
+    pub fn close_on_stall<C>(&self, component: C)
+    where
+        C: Closeable + Send + 'static,
+    {
+        self.on_stall(move |_| component.close());
+    }
+    /// Drops the value on the first stall. Can be used to close channels, which are closed when
+    /// their last sender or receiver is dropped
+    pub fn drop_on_stall<T>(&self, value: T)
+    where
+        T: Send + 'static,
+    {
+        let mut value = Some(value);
+        self.on_stall(move |_| {
+            value.take();
+        });
+    }
+    /// Checks the tasks, calls the handlers for new stalls and returns them
+    pub fn check(&self) -> Vec<Stall> {
+        let stalls: Vec<Stall> = self
+            .inner
+            .tasks
+            .lock()
+            .iter()
+            .filter_map(|slot| {
+                let last_kick = Monotonic::from_nanos(slot.last_kick.load(Ordering::SeqCst));
+                let op = Operation::new_for_monotonic(last_kick, slot.timeout);
+                if op.remaining().is_ok() || slot.stalled.swap(true, Ordering::SeqCst) {
+                    return None;
+                }
+                Some(Stall {
+                    task: slot.name.clone(),
+                    timeout: slot.timeout,
+                    overdue: last_kick.elapsed().saturating_sub(slot.timeout),
+                })
+            })
+            .collect();
+        if !stalls.is_empty() {
+            let _dispatch = self.inner.dispatch.lock();
+            let mut handlers = mem::take(&mut *self.inner.handlers.lock());
+            for stall in &stalls {
+                tracing::error!(
+                    task = stall.task(),
+                    overdue = ?stall.overdue(),
+                    "watchdog: task stalled"
+                );
+                for handler in handlers.iter_mut() {
+                    handler(stall);
+                }
+            }
+            // keep the handlers, registered by the handlers being called
+            let mut registered = self.inner.handlers.lock();
+            handlers.append(&mut registered);
+            *registered = handlers;
+        }
+        stalls
+    }
+    /// Spawns a monitor thread, which checks the tasks with the given period. If the parameters
+    /// can not be applied, the thread is terminated with the error. The thread is stopped when the
+    /// last [`Watchdog`] clone is dropped (the task handles do not keep the watchdog alive)
+    pub fn spawn(
+        &self,
+        period: Duration,
+        params: thread_rt::Params,
+    ) -> Result<thread::JoinHandle<Result<()>>> {
+        if period.is_zero() {
+            return Err(Error::InvalidData("the period must be non-zero".to_owned()));
+        }
+        let inner = Arc::downgrade(&self.inner);
+        Ok(thread::Builder::new()
+            .name("rtsc-watchdog".to_owned())
+            .spawn(move || {
+                thread_rt::apply_for_current(&params)?;
+                for _ in time::interval(period) {
+                    let Some(inner) = inner.upgrade() else {
+                        break;
+                    };
+                    Watchdog { inner }.check();
+                }
+                Ok(())
+            })?)
+    }
+}
+
+/// A task watchdog handle
+pub struct WatchdogHandle {
+    slot: Arc<TaskSlot>,
+    watchdog: Weak<WatchdogInner>,
+}
+
+impl WatchdogHandle {
+    /// Notifies the watchdog that the task is alive (lock-free)
+    #[inline]
+    pub fn kick(&self) {
+        self.slot
+            .last_kick
+            .store(monotonic_nanos(), Ordering::SeqCst);
+        self.slot.stalled.store(false, Ordering::SeqCst);
+    }
+    /// The task name
+    pub fn name(&self) -> &str {
+        &self.slot.name
+    }
+}
+
+impl Drop for WatchdogHandle {
+    fn drop(&mut self) {
+        if let Some(inner) = self.watchdog.upgrade() {
+            inner
+                .tasks
+                .lock()
+                .retain(|slot| !Arc::ptr_eq(slot, &self.slot));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::Watchdog;
+    use crate::{cell::DataCell, channel::Bounded, thread_rt::Params, Error};
+
+    #[test]
+    fn test_watchdog() {
+        let watchdog = Watchdog::new();
+        let alive = watchdog.register("alive", Duration::from_millis(50));
+        let stalled = watchdog.register("stalled", Duration::from_millis(10));
+        let cell: DataCell<u32> = DataCell::new();
+        watchdog.close_on_stall(cell.clone());
+        let stalls = Arc::new(AtomicUsize::new(0));
+        let stalls_c = stalls.clone();
+        watchdog.on_stall(move |_| {
+            stalls_c.fetch_add(1, Ordering::SeqCst);
+        });
+        thread::sleep(Duration::from_millis(20));
+        alive.kick();
+        let result = watchdog.check();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].task(), "stalled");
+        assert!(result[0].overdue() >= Duration::from_millis(10));
+        assert!(cell.is_closed());
+        // reported once per stall
+        assert!(watchdog.check().is_empty());
+        stalled.kick();
+        assert!(watchdog.check().is_empty());
+        drop(stalled);
+        thread::sleep(Duration::from_millis(20));
+        assert!(watchdog.check().is_empty());
+        assert_eq!(stalls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_watchdog_reentrant_handler() {
+        let watchdog = Watchdog::new();
+        let task = watchdog.register("task", Duration::from_millis(1));
+        let stalls = Arc::new(AtomicUsize::new(0));
+        let (watchdog_c, stalls_c) = (watchdog.clone(), stalls.clone());
+        watchdog.on_stall(move |_| {
+            let stalls_c = stalls_c.clone();
+            watchdog_c.on_stall(move |_| {
+                stalls_c.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(watchdog.check().len(), 1);
+        task.kick();
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(watchdog.check().len(), 1);
+        // the handler, registered by the first handler, is kept
+        assert_eq!(stalls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_watchdog_monitor() {
+        let watchdog = Watchdog::new();
+        let Bounded { tx, rx } = Bounded::<u32>::new(1);
+        watchdog.drop_on_stall(tx);
+        let handle = watchdog.register("task", Duration::from_millis(10));
+        let monitor = watchdog
+            .spawn(Duration::from_millis(5), Params::new())
+            .unwrap();
+        // the channel is closed on the stall
+        assert!(matches!(rx.recv(), Err(Error::ChannelClosed)));
+        drop(handle);
+        drop(watchdog);
+        monitor.join().unwrap().unwrap();
+    }
+}